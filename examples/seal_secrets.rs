@@ -0,0 +1,23 @@
+//! 生成加密密钥文件
+//!
+//! ```sh
+//! openssl rand -hex 32 > secrets.key
+//! echo '{"application.pdd.client_secret": "..."}' \
+//!     | cargo run --example seal_secrets -- secrets.key > configuration/secrets.enc
+//! ```
+use std::{collections::BTreeMap, io::Read};
+
+use kuai_saver::configuration::secrets::EncryptedFileProvider;
+
+fn main() -> anyhow::Result<()> {
+    let key_file = std::env::args()
+        .nth(1)
+        .ok_or_else(|| anyhow::anyhow!("用法: seal_secrets <密钥文件>"))?;
+
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let secrets: BTreeMap<String, String> = serde_json::from_str(&input)?;
+
+    println!("{}", EncryptedFileProvider::seal(&key_file, &secrets)?);
+    Ok(())
+}
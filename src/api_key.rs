@@ -0,0 +1,204 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+use chrono::NaiveDate;
+use ring::{
+    digest::{SHA256, digest},
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
+use uuid::Uuid;
+
+use crate::{
+    entity::api_keys,
+    error::{AppError, AppResult},
+    route::AppState,
+};
+
+/// 携带 API 密钥的请求头
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// 密钥前缀，便于在日志和代码仓库中识别泄露的密钥
+const KEY_PREFIX: &str = "ks_";
+
+/// 展示用的密钥开头长度，包含前缀
+const DISPLAY_PREFIX_LEN: usize = 11;
+
+/// API 密钥的授权范围
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, AsRefStr, Display, EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ApiScope {
+    /// 调用转链、推荐和搜索等生成推广链接的接口
+    Translate,
+    /// 代用户创建和删除降价提醒订阅
+    Subscribe,
+}
+
+/// 新签发的密钥，明文只在签发时返回一次
+pub struct GeneratedKey {
+    pub key: String,
+    pub prefix: String,
+    pub hash: String,
+}
+
+/// 生成随机密钥
+pub fn generate_key() -> GeneratedKey {
+    let mut bytes = [0u8; 24];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("Failed to generate random bytes");
+    let key = format!("{}{}", KEY_PREFIX, hex::encode(bytes));
+    GeneratedKey {
+        prefix: key[..DISPLAY_PREFIX_LEN].to_string(),
+        hash: hash_key(&key),
+        key,
+    }
+}
+
+/// 密钥的 SHA-256 摘要；密钥本身足够随机，不需要加盐
+pub fn hash_key(key: &str) -> String {
+    hex::encode(digest(&SHA256, key.as_bytes()))
+}
+
+/// 通过 API 密钥认证的调用方
+///
+/// 作为提取器使用时只校验密钥，不检查授权范围，也不计入频率限制和配额
+#[derive(Debug, Clone)]
+pub struct ApiKeyIdentity {
+    pub id: Uuid,
+    pub name: String,
+    pub owner: String,
+    pub pid: Option<String>,
+    pub scopes: Vec<ApiScope>,
+    pub rate_limit_per_minute: u32,
+    pub daily_quota: i64,
+}
+
+impl ApiKeyIdentity {
+    pub fn allows(&self, scope: ApiScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+impl From<api_keys::Model> for ApiKeyIdentity {
+    fn from(model: api_keys::Model) -> Self {
+        ApiKeyIdentity {
+            id: model.id,
+            name: model.name,
+            owner: model.owner,
+            pid: model.pid,
+            // 忽略已不再支持的授权范围
+            scopes: model
+                .scopes
+                .split_whitespace()
+                .filter_map(|scope| scope.parse().ok())
+                .collect(),
+            rate_limit_per_minute: model.rate_limit_per_minute.max(0) as u32,
+            daily_quota: model.daily_quota,
+        }
+    }
+}
+
+/// 校验请求头中的密钥
+pub async fn authenticate(state: &AppState, key: &str) -> AppResult<ApiKeyIdentity> {
+    // 格式不对的密钥不必查库
+    if !key.starts_with(KEY_PREFIX) || key.len() != KEY_PREFIX.len() + 48 {
+        return Err(AppError::Unauthorized("API 密钥无效或已吊销".to_string()));
+    }
+    api_keys::find_active_by_hash(&state.connection_pool(), &hash_key(key))
+        .await?
+        .map(ApiKeyIdentity::from)
+        .ok_or_else(|| AppError::Unauthorized("API 密钥无效或已吊销".to_string()))
+}
+
+/// 读取请求头中的密钥，没有携带时返回 `None`
+pub fn key_from_parts(parts: &Parts) -> AppResult<Option<&str>> {
+    parts
+        .headers
+        .get(API_KEY_HEADER)
+        .map(|v| {
+            v.to_str()
+                .map_err(|_| AppError::Unauthorized("API 密钥格式不正确".to_string()))
+        })
+        .transpose()
+}
+
+impl FromRequestParts<AppState> for ApiKeyIdentity {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> AppResult<Self> {
+        // 已经过 require_api_key 校验的请求直接复用结果
+        if let Some(identity) = parts.extensions.get::<ApiKeyIdentity>() {
+            return Ok(identity.clone());
+        }
+        let key = key_from_parts(parts)?
+            .ok_or_else(|| AppError::Unauthorized("缺少 API 密钥".to_string()))?;
+        authenticate(state, key).await
+    }
+}
+
+/// 按密钥限制每分钟请求数的令牌桶，只在当前实例内生效
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    buckets: Arc<Mutex<HashMap<Uuid, Bucket>>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    /// 取一个令牌，桶容量为每分钟请求数，令牌按每分钟请求数匀速补充
+    pub fn try_acquire(&self, key: Uuid, per_minute: u32) -> bool {
+        let capacity = f64::from(per_minute);
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * capacity / 60.0).min(capacity);
+        bucket.updated_at = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// 被拒绝的请求数，先在内存中累计，由后台任务定期写入数据库
+///
+/// 被限流的请求往往集中出现，逐个写库会让数据库承受与限流前相同的压力
+#[derive(Clone, Default)]
+pub struct RejectionCounter {
+    counts: Arc<Mutex<HashMap<(Uuid, NaiveDate), i64>>>,
+}
+
+impl RejectionCounter {
+    pub fn record(&self, key: Uuid, day: NaiveDate) {
+        *self.counts.lock().unwrap().entry((key, day)).or_default() += 1;
+    }
+
+    /// 取出并清空累计的次数
+    pub fn take(&self) -> HashMap<(Uuid, NaiveDate), i64> {
+        std::mem::take(&mut *self.counts.lock().unwrap())
+    }
+
+    /// 写入失败时放回，下次一起写入
+    pub fn restore(&self, key: Uuid, day: NaiveDate, count: i64) {
+        *self.counts.lock().unwrap().entry((key, day)).or_default() += count;
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{configuration::application::CacheSettings, metrics};
+
+/// 带过期时间的内存缓存
+#[derive(Clone)]
+pub struct TtlCache<V> {
+    inner: Arc<TtlCacheInner<V>>,
+}
+
+struct TtlCacheInner<V> {
+    /// 指标中的缓存名称
+    name: &'static str,
+    entries: Mutex<HashMap<String, (Instant, V)>>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl<V: Clone> TtlCache<V> {
+    pub fn new(name: &'static str, settings: &CacheSettings) -> Self {
+        Self {
+            inner: Arc::new(TtlCacheInner {
+                name,
+                entries: Mutex::new(HashMap::new()),
+                ttl: Duration::from_secs(settings.ttl_secs),
+                capacity: settings.capacity,
+            }),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<V> {
+        let mut entries = self.inner.entries.lock().unwrap();
+        let value = match entries.get(key) {
+            Some((expires_at, value)) if *expires_at > Instant::now() => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        };
+        metrics::record_cache_request(self.inner.name, value.is_some());
+        value
+    }
+
+    pub fn insert(&self, key: String, value: V) {
+        if self.inner.capacity == 0 || self.inner.ttl.is_zero() {
+            return;
+        }
+        let now = Instant::now();
+        let mut entries = self.inner.entries.lock().unwrap();
+        if entries.len() >= self.inner.capacity {
+            // 先清理过期项，仍然满了就整体清空，避免无限增长
+            entries.retain(|_, (expires_at, _)| *expires_at > now);
+            if entries.len() >= self.inner.capacity {
+                entries.clear();
+            }
+        }
+        entries.insert(key, (now + self.inner.ttl, value));
+    }
+
+    /// 当前条目数，包含尚未清理的过期项
+    pub fn size(&self) -> usize {
+        self.inner.entries.lock().unwrap().len()
+    }
+}
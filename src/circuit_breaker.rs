@@ -0,0 +1,178 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use strum::AsRefStr;
+
+use crate::Platform;
+
+/// 连续失败多少次后熔断
+const FAILURE_THRESHOLD: u32 = 5;
+
+/// 熔断后多久允许试探请求
+const OPEN_DURATION: Duration = Duration::from_secs(30);
+
+/// 试探请求超过这个时间仍未结束时允许发起新的试探，避免请求被取消后一直无法恢复
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 各平台的熔断器，由 `AppState` 持有
+#[derive(Clone, Default)]
+pub struct CircuitBreakers {
+    inner: Arc<Mutex<HashMap<Platform, Arc<CircuitBreaker>>>>,
+}
+
+impl CircuitBreakers {
+    /// 获取平台对应的熔断器，同一平台的所有转链器共享
+    pub fn for_platform(&self, platform: Platform) -> Arc<CircuitBreaker> {
+        self.inner
+            .lock()
+            .unwrap()
+            .entry(platform)
+            .or_default()
+            .clone()
+    }
+}
+
+/// 熔断器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BreakerState {
+    /// 正常放行
+    Closed,
+    /// 熔断中，拒绝请求
+    Open,
+    /// 熔断到期，放行一次试探请求
+    HalfOpen,
+}
+
+/// 上游接口熔断器
+///
+/// 连续失败达到阈值后熔断一段时间，到期后只放行一次试探请求，试探成功即恢复
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    inner: Mutex<BreakerInner>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerInner {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// 半开状态下正在进行的试探请求的开始时间
+    probe_started_at: Option<Instant>,
+}
+
+impl BreakerInner {
+    fn state(&self) -> BreakerState {
+        match self.opened_at {
+            None => BreakerState::Closed,
+            Some(opened_at) if opened_at.elapsed() < OPEN_DURATION => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+}
+
+impl CircuitBreaker {
+    pub fn state(&self) -> BreakerState {
+        self.inner.lock().unwrap().state()
+    }
+
+    /// 是否允许发起请求，半开状态下同一时间只放行一个试探请求
+    pub fn allow(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.state() {
+            BreakerState::Closed => true,
+            BreakerState::Open => false,
+            BreakerState::HalfOpen => {
+                let probing = inner
+                    .probe_started_at
+                    .is_some_and(|t| t.elapsed() < PROBE_TIMEOUT);
+                if !probing {
+                    inner.probe_started_at = Some(Instant::now());
+                }
+                !probing
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+        inner.probe_started_at = None;
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+        inner.probe_started_at = None;
+        if inner.consecutive_failures >= FAILURE_THRESHOLD {
+            // 半开状态下试探失败会重新计时
+            inner.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn half_open() -> CircuitBreaker {
+        let breaker = CircuitBreaker::default();
+        for _ in 0..FAILURE_THRESHOLD {
+            breaker.record_failure();
+        }
+        assert_eq!(breaker.state(), BreakerState::Open);
+        breaker.inner.lock().unwrap().opened_at = Some(Instant::now() - OPEN_DURATION);
+        breaker
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::default();
+        for _ in 1..FAILURE_THRESHOLD {
+            breaker.record_failure();
+        }
+        breaker.record_success();
+        for _ in 1..FAILURE_THRESHOLD {
+            breaker.record_failure();
+        }
+        assert!(breaker.allow());
+
+        breaker.record_failure();
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn half_open_allows_a_single_probe() {
+        let breaker = half_open();
+
+        assert!(breaker.allow());
+        assert!(!breaker.allow());
+
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert!(breaker.allow());
+    }
+
+    #[test]
+    fn failed_probe_reopens() {
+        let breaker = half_open();
+
+        assert!(breaker.allow());
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+    }
+
+    #[test]
+    fn abandoned_probe_is_replaced_after_timeout() {
+        let breaker = half_open();
+        assert!(breaker.allow());
+
+        breaker.inner.lock().unwrap().probe_started_at = Some(Instant::now() - PROBE_TIMEOUT);
+        assert!(breaker.allow());
+    }
+}
@@ -0,0 +1,300 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, bail};
+use chrono::{DateTime, Duration, Utc};
+use clap::{Args, Parser, Subcommand};
+use sea_orm::Database;
+use serde_json::Value;
+use tracing::info;
+
+use crate::{
+    circuit_breaker::CircuitBreakers,
+    configuration::{LoadOptions, Settings, environment::Environment, secrets::SECRET_PATHS},
+    job, migration,
+    route::translate::{identify_platform, translator_from_settings},
+    startup::Application,
+    telemetry,
+};
+
+/// 快省：电商推广链接转换服务
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+
+    /// 只检查配置，等同于 check-config 子命令
+    #[arg(long, hide = true)]
+    pub check_config: bool,
+
+    /// 默认启动服务
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// 所有子命令共用的配置加载参数
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+    /// 配置文件目录，默认为当前目录下的 configuration
+    #[arg(long, global = true, env = "APP_CONFIG_DIR")]
+    pub config_dir: Option<PathBuf>,
+
+    /// 运行环境，决定加载哪个环境配置文件
+    #[arg(long, global = true, env = "APP_ENVIRONMENT")]
+    pub environment: Option<Environment>,
+
+    /// 额外的配置文件，覆盖配置目录中的配置
+    #[arg(long = "config", global = true, env = "APP_CONFIG_FILE")]
+    pub config_file: Option<PathBuf>,
+}
+
+impl ConfigArgs {
+    pub fn load_options(&self) -> anyhow::Result<LoadOptions> {
+        let mut options = LoadOptions::from_env()?;
+        if let Some(dir) = &self.config_dir {
+            options.config_dir = dir.clone();
+        }
+        if let Some(environment) = self.environment {
+            options.environment = environment;
+        }
+        if let Some(file) = &self.config_file {
+            options.config_file = Some(file.clone());
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 启动 HTTP 服务
+    Serve,
+    /// 转换一个商品链接并输出商品信息
+    Translate {
+        /// 商品链接
+        url: String,
+    },
+    /// 推广订单
+    Orders {
+        #[command(subcommand)]
+        command: OrdersCommand,
+    },
+    /// 执行数据库表结构变更
+    Migrate,
+    /// 检查配置，有问题时以非零状态码退出
+    CheckConfig,
+    /// 查看配置
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum OrdersCommand {
+    /// 从平台同步有更新的订单
+    Sync {
+        /// 起始时间，RFC 3339 格式或相对时长如 `6h`、`7d`
+        #[arg(long, value_parser = parse_since, default_value = "24h")]
+        since: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// 以 JSON 格式输出合并后的配置
+    Print {
+        /// 隐藏密钥类配置项
+        #[arg(long)]
+        redacted: bool,
+    },
+}
+
+/// 执行命令行指定的操作
+pub async fn run(cli: Cli) -> anyhow::Result<()> {
+    let options = cli.config.load_options()?;
+    let command = match cli.command {
+        _ if cli.check_config => Command::CheckConfig,
+        Some(command) => command,
+        None => Command::Serve,
+    };
+
+    match command {
+        Command::Serve => serve(options).await,
+        Command::Translate { url } => translate(&options, &url).await,
+        Command::Orders {
+            command: OrdersCommand::Sync { since },
+        } => sync_orders(&options, since).await,
+        Command::Migrate => migrate(&options).await,
+        Command::CheckConfig => check_config(&options),
+        Command::Config {
+            command: ConfigCommand::Print { redacted },
+        } => print_config(&options, redacted),
+    }
+}
+
+async fn serve(options: LoadOptions) -> anyhow::Result<()> {
+    let (source, layers) = Settings::layered_source(&options)?;
+    let configuration: Settings = source.clone().try_deserialize()?;
+    configuration.validate()?;
+
+    let (subscriber, guard) = telemetry::init_tracing(configuration.log.clone())?;
+    telemetry::set_subscriber(subscriber);
+    info!("运行环境: {}", options.environment);
+    for layer in layers {
+        info!(overridden = ?layer.overridden, "已加载配置 {}", layer.name);
+    }
+
+    let mut app = Application::build(configuration, guard.log_filters()).await?;
+    app.watch_config(options, source)?;
+    app.run_until_stopped().await?;
+
+    // 退出前刷新缓冲中的日志和链路追踪数据
+    drop(guard);
+    Ok(())
+}
+
+async fn translate(options: &LoadOptions, url: &str) -> anyhow::Result<()> {
+    let settings = Settings::load(options)?;
+    let platform = identify_platform(url).context("不支持的链接")?;
+    let translator =
+        translator_from_settings(platform, &settings.application, &CircuitBreakers::default())?;
+
+    let mut good_info = translator.search(url).await?;
+    good_info.short_url = translator.gen_short_url(url).await?;
+    println!("{}", serde_json::to_string_pretty(&good_info)?);
+    Ok(())
+}
+
+async fn sync_orders(options: &LoadOptions, since: DateTime<Utc>) -> anyhow::Result<()> {
+    let settings = Settings::load(options)?;
+    let (subscriber, _guard) = telemetry::init_tracing(settings.log.clone())?;
+    telemetry::set_subscriber(subscriber);
+
+    let db = Database::connect(settings.db.build()).await?;
+    let synced = job::sync_orders(&db, &settings.application, since, Utc::now()).await?;
+    println!("订单同步完成，共 {} 条", synced);
+    Ok(())
+}
+
+async fn migrate(options: &LoadOptions) -> anyhow::Result<()> {
+    let settings = Settings::load(options)?;
+    let db = Database::connect(settings.db.build()).await?;
+    let executed = migration::run(&db).await?;
+    if executed.is_empty() {
+        println!("表结构已是最新");
+    }
+    for migration in executed {
+        println!("已执行 {} {}", migration.version, migration.name);
+    }
+    Ok(())
+}
+
+fn check_config(options: &LoadOptions) -> anyhow::Result<()> {
+    let result = Settings::load(options).and_then(|settings| Ok(settings.validate()?));
+    match result {
+        Ok(()) => {
+            println!("配置检查通过");
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("{:#}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_config(options: &LoadOptions, redacted: bool) -> anyhow::Result<()> {
+    let mut config: Value = Settings::source(options)?.try_deserialize()?;
+    if redacted {
+        redact_secrets(&mut config);
+    }
+    println!("{}", serde_json::to_string_pretty(&config)?);
+    Ok(())
+}
+
+/// 将密钥类配置项替换为 `<redacted>`，未配置的配置项保持不变
+pub fn redact_secrets(config: &mut Value) {
+    for path in SECRET_PATHS {
+        let segments: Vec<_> = path.split('.').collect();
+        redact(config, &segments);
+    }
+}
+
+fn redact(value: &mut Value, segments: &[&str]) {
+    let Some((first, rest)) = segments.split_first() else {
+        *value = Value::String("<redacted>".to_string());
+        return;
+    };
+    if let Some(key) = first.strip_suffix("[]") {
+        if let Some(Value::Array(items)) = value.get_mut(key) {
+            for item in items {
+                redact(item, rest);
+            }
+        }
+    } else if let Some(child) = value.get_mut(*first) {
+        redact(child, rest);
+    }
+}
+
+/// 解析 RFC 3339 时间或 `6h`、`7d` 这样的相对时长
+fn parse_since(value: &str) -> anyhow::Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let Some(unit) = value.chars().last() else {
+        bail!("时间不能为空");
+    };
+    let to_duration = match unit {
+        'm' => Duration::minutes,
+        'h' => Duration::hours,
+        'd' => Duration::days,
+        _ => bail!("无效的时间单位 \"{}\"，支持 m、h、d", unit),
+    };
+    let amount = &value[..value.len() - unit.len_utf8()];
+    // 只接受非负整数，u32 范围内的时长不会溢出
+    let amount: u32 = amount
+        .parse()
+        .with_context(|| format!("无效的时长 \"{}\"", value))?;
+    Utc::now()
+        .checked_sub_signed(to_duration(amount.into()))
+        .with_context(|| format!("时长 \"{}\" 超出范围", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_ago(since: DateTime<Utc>, expected: Duration) {
+        let error = (Utc::now() - expected - since).num_seconds().abs();
+        assert!(error < 5, "since {} is {}s off", since, error);
+    }
+
+    #[test]
+    fn parses_relative_durations() {
+        assert_ago(parse_since("30m").unwrap(), Duration::minutes(30));
+        assert_ago(parse_since("6h").unwrap(), Duration::hours(6));
+        assert_ago(parse_since("7d").unwrap(), Duration::days(7));
+    }
+
+    #[test]
+    fn parses_rfc3339_times() {
+        let since = parse_since("2025-06-01T08:00:00+08:00").unwrap();
+        assert_eq!(since.to_rfc3339(), "2025-06-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn rejects_unknown_and_multi_byte_units() {
+        assert!(parse_since("6天").is_err());
+        assert!(parse_since("6w").is_err());
+        assert!(parse_since("6").is_err());
+        assert!(parse_since("").is_err());
+    }
+
+    #[test]
+    fn rejects_negative_and_malformed_amounts() {
+        assert!(parse_since("-6h").is_err());
+        assert!(parse_since("h").is_err());
+        assert!(parse_since("1.5h").is_err());
+        assert!(parse_since("99999999999d").is_err());
+    }
+}
@@ -1,5 +1,8 @@
-use anyhow::Result;
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::{Context, Result};
 use serde::Deserialize;
+use serde_json::Value;
 
 use crate::configuration::environment::Environment;
 
@@ -7,37 +10,169 @@ pub mod application;
 pub mod database;
 pub mod environment;
 pub mod logging;
+pub mod secrets;
+pub mod validation;
 
 pub use application::ApplicationSettings;
 pub use database::DatabaseSettings;
 pub use logging::LogSettings;
+pub use secrets::SecretsSettings;
 
 #[derive(Deserialize, Clone)]
 pub struct Settings {
     pub application: ApplicationSettings,
     pub log: LogSettings,
     pub db: DatabaseSettings,
+    /// 密钥提供者，为未直接配置的密钥类配置项提供取值
+    #[serde(default)]
+    pub secrets: Option<SecretsSettings>,
+}
+
+/// 本地覆盖配置文件名，不提交到仓库，存在时在环境配置之后加载
+pub const LOCAL_OVERRIDE_FILE: &str = "local.override.yaml";
+
+/// 配置加载选项
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    /// 配置文件所在目录
+    pub config_dir: PathBuf,
+    pub environment: Environment,
+    /// 额外的配置文件，在配置目录中的文件之后、环境变量之前加载
+    pub config_file: Option<PathBuf>,
+    /// 代替进程环境变量加载的 `APP_*` 变量，为 `None` 时读取进程环境变量
+    pub env: Option<config::Map<String, String>>,
+}
+
+impl LoadOptions {
+    /// 默认读取当前目录下的 `configuration`，环境由 `APP_ENVIRONMENT` 指定
+    pub fn from_env() -> Result<Self> {
+        let environment = match std::env::var("APP_ENVIRONMENT") {
+            Ok(env) => env.parse()?,
+            Err(_) => Environment::default(),
+        };
+        Ok(Self {
+            config_dir: std::env::current_dir()?.join("configuration"),
+            environment,
+            config_file: None,
+            env: None,
+        })
+    }
+}
+
+/// 一层配置来源及其覆盖的配置项
+#[derive(Debug, Clone)]
+pub struct ConfigLayer {
+    /// 配置文件路径，环境变量层为 `环境变量 APP_*`
+    pub name: String,
+    /// 覆盖了之前各层取值的配置项路径
+    pub overridden: Vec<String>,
 }
 
 impl Settings {
-    pub fn load() -> Result<Settings> {
-        let current_dir = std::env::current_dir()?;
-        let configuration = current_dir.join("configuration");
-
-        let environment: Environment = std::env::var("APP_ENVIRONMENT")
-            .map_or(Environment::Local, |env| env.parse().unwrap_or_default());
-
-        let environment_filename = format!("{}.yaml", environment);
-
-        let settings = config::Config::builder()
-            .add_source(config::File::from(configuration.join("base.yaml")))
-            .add_source(config::File::from(configuration.join(environment_filename)))
-            .add_source(
-                config::Environment::with_prefix("APP")
-                    .separator("__")
-                    .prefix_separator("_"),
-            )
-            .build()?;
-        settings.try_deserialize().map_err(|e| anyhow::anyhow!(e))
+    pub fn load(options: &LoadOptions) -> Result<Settings> {
+        Self::source(options)?
+            .try_deserialize()
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// 合并各层配置并填充密钥后的原始配置树
+    pub fn source(options: &LoadOptions) -> Result<config::Config> {
+        Ok(Self::layered_source(options)?.0)
+    }
+
+    /// 同 [`Settings::source`]，同时返回实际加载的各层配置
+    ///
+    /// 依次加载 `base.yaml`、`{环境}.yaml`、可选的 `local.override.yaml`、`--config`
+    /// 指定的文件和 `APP_` 开头的环境变量，后加载的覆盖先加载的
+    pub fn layered_source(options: &LoadOptions) -> Result<(config::Config, Vec<ConfigLayer>)> {
+        let dir = &options.config_dir;
+        let mut files = vec![
+            (dir.join("base.yaml"), true),
+            (dir.join(format!("{}.yaml", options.environment)), true),
+            (dir.join(LOCAL_OVERRIDE_FILE), false),
+        ];
+        if let Some(file) = &options.config_file {
+            files.push((file.clone(), true));
+        }
+
+        let mut builder = config::Config::builder();
+        let mut layers = Vec::new();
+        let mut loaded = BTreeMap::new();
+        for (path, required) in files {
+            if !required && !path.exists() {
+                continue;
+            }
+            let source = config::File::from(path.as_path());
+            let layer = config::Config::builder()
+                .add_source(source.clone())
+                .build()
+                .with_context(|| format!("加载配置文件 {} 失败", path.display()))?;
+            layers.push(record_layer(
+                path.display().to_string(),
+                &layer,
+                &mut loaded,
+            )?);
+            builder = builder.add_source(source);
+        }
+
+        let env = config::Environment::with_prefix("APP")
+            .separator("__")
+            .prefix_separator("_")
+            .source(options.env.clone());
+        let layer = config::Config::builder().add_source(env.clone()).build()?;
+        layers.push(record_layer(
+            "环境变量 APP_*".to_string(),
+            &layer,
+            &mut loaded,
+        )?);
+
+        let settings = builder.add_source(env).build()?;
+        Ok((secrets::resolve(settings)?, layers))
+    }
+}
+
+/// 记录一层配置覆盖的配置项，并把该层的配置项并入已加载的配置项
+fn record_layer(
+    name: String,
+    layer: &config::Config,
+    loaded: &mut BTreeMap<String, Value>,
+) -> Result<ConfigLayer> {
+    let mut overridden = Vec::new();
+    for (key, value) in flatten_config(layer)? {
+        if loaded.insert(key.clone(), value).is_some() {
+            overridden.push(key);
+        }
+    }
+    Ok(ConfigLayer { name, overridden })
+}
+
+/// 展开为 `路径 -> 值` 的形式，数组元素路径形如 `log.targets[0].level`
+pub(crate) fn flatten_config(config: &config::Config) -> Result<BTreeMap<String, Value>> {
+    let tree: Value = config.clone().try_deserialize()?;
+    let mut flat = BTreeMap::new();
+    flatten(String::new(), tree, &mut flat);
+    Ok(flat)
+}
+
+fn flatten(path: String, value: Value, flat: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = if path.is_empty() {
+                    key
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                flatten(path, value, flat);
+            }
+        }
+        Value::Array(items) => {
+            for (i, value) in items.into_iter().enumerate() {
+                flatten(format!("{}[{}]", path, i), value, flat);
+            }
+        }
+        value => {
+            flat.insert(path, value);
+        }
     }
 }
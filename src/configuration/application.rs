@@ -1,10 +1,79 @@
 use secrecy::SecretString;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Clone)]
 pub struct ApplicationSettings {
+    /// 监听地址，默认监听所有网卡
+    #[serde(default = "default_host")]
+    pub host: String,
+    /// 监听端口，为 0 时由系统分配
     pub port: u16,
+    /// 配置后在本服务终止 TLS，使用 HTTPS 对外提供服务
+    #[serde(default)]
+    pub tls: Option<TlsSettings>,
+    /// 配置后改为监听 Unix 域套接字，忽略 `host` 和 `port`
+    #[serde(default)]
+    pub unix_socket: Option<String>,
+    /// 收到退出信号后等待请求和后台任务结束的最长时间，单位为秒
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
     pub pdd: PddSettings,
+    #[serde(default)]
+    pub price_history: PriceHistorySettings,
+    #[serde(default)]
+    pub price_alert: PriceAlertSettings,
+    #[serde(default)]
+    pub cache: CacheSettings,
+    /// 管理接口配置，不配置时管理接口不可用
+    #[serde(default)]
+    pub admin: Option<AdminSettings>,
+    #[serde(default)]
+    pub api_keys: ApiKeySettings,
+}
+
+fn default_host() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    30
+}
+
+#[derive(Deserialize, Clone)]
+pub struct TlsSettings {
+    /// PEM 格式证书链路径
+    pub cert_path: String,
+    /// PEM 格式私钥路径
+    pub key_path: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct AdminSettings {
+    /// 管理员的访问令牌，通过 `Authorization: Bearer <token>` 传入，拥有全部权限
+    pub token: SecretString,
+    /// 运营人员，各自使用独立令牌，按角色限制可用的接口
+    #[serde(default)]
+    pub operators: Vec<OperatorSettings>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct OperatorSettings {
+    /// 运营人员名称，记录在日志中
+    pub name: String,
+    pub token: SecretString,
+    pub role: AdminRole,
+}
+
+/// 管理接口角色，后面的角色拥有前面角色的全部权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminRole {
+    /// 只读
+    Viewer,
+    /// 可以执行订单重新同步等运营操作
+    Operator,
+    /// 可以修改日志过滤指令等系统设置
+    Admin,
 }
 
 #[derive(Deserialize, Clone)]
@@ -15,4 +84,175 @@ pub struct PddSettings {
     pub domain: String,
     pub api_good_search: String,
     pub api_gen_short_url: String,
+    #[serde(default = "default_api_goods_recommend")]
+    pub api_goods_recommend: String,
+    #[serde(default = "default_api_top_goods")]
+    pub api_top_goods: String,
+    #[serde(default = "default_api_promotion_url_generate")]
+    pub api_promotion_url_generate: String,
+    #[serde(default = "default_api_order_list_increment")]
+    pub api_order_list_increment: String,
+    #[serde(default = "default_api_order_detail")]
+    pub api_order_detail: String,
+    /// 录制或回放上游响应，不配置时直接请求网关
+    #[serde(default)]
+    pub fixtures: Option<FixtureSettings>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct FixtureSettings {
+    pub mode: FixtureMode,
+    /// 录制数据所在目录
+    pub dir: String,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FixtureMode {
+    /// 正常请求网关，同时把请求和响应写入录制目录
+    Record,
+    /// 不请求网关，从录制目录读取响应
+    Replay,
+}
+
+fn default_api_goods_recommend() -> String {
+    "pdd.ddk.goods.recommend.get".to_string()
+}
+
+fn default_api_top_goods() -> String {
+    "pdd.ddk.top.goods.list.query".to_string()
+}
+
+fn default_api_promotion_url_generate() -> String {
+    "pdd.ddk.goods.promotion.url.generate".to_string()
+}
+
+fn default_api_order_list_increment() -> String {
+    "pdd.ddk.order.list.increment.get".to_string()
+}
+
+fn default_api_order_detail() -> String {
+    "pdd.ddk.order.detail.get".to_string()
+}
+
+#[derive(Deserialize, Clone)]
+pub struct PriceHistorySettings {
+    /// 价格刷新间隔，单位为秒
+    pub refresh_interval_secs: u64,
+    /// 最近多少天内被转链过的商品会被定时刷新
+    pub track_days: i64,
+    /// 每轮最多刷新的商品数量
+    pub batch_size: u64,
+}
+
+impl Default for PriceHistorySettings {
+    fn default() -> Self {
+        Self {
+            refresh_interval_secs: 3600,
+            track_days: 7,
+            batch_size: 100,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct PriceAlertSettings {
+    /// 降价检查间隔，单位为秒
+    pub check_interval_secs: u64,
+    /// 每轮最多检查的订阅数量，超出时下一轮继续检查其余订阅
+    pub batch_size: u64,
+    /// 同一订阅最多发送失败的次数，达到后不再重试
+    #[serde(default = "default_max_delivery_attempts")]
+    pub max_delivery_attempts: u32,
+    #[serde(default)]
+    pub notifier: NotifierSettings,
+}
+
+fn default_max_delivery_attempts() -> u32 {
+    5
+}
+
+impl Default for PriceAlertSettings {
+    fn default() -> Self {
+        Self {
+            check_interval_secs: 600,
+            batch_size: 200,
+            max_delivery_attempts: default_max_delivery_attempts(),
+            notifier: NotifierSettings::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum NotifierSettings {
+    /// 只打印日志
+    #[default]
+    Log,
+    /// 写入本地文件
+    File { path: String },
+    /// 通过 SMTP 发送邮件
+    Smtp(SmtpSettings),
+}
+
+#[derive(Deserialize, Clone)]
+pub struct SmtpSettings {
+    pub host: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    pub username: String,
+    pub password: SecretString,
+    /// 发件人，如 `快省 <noreply@example.com>`
+    pub from: String,
+}
+
+fn default_smtp_port() -> u16 {
+    465
+}
+
+#[derive(Deserialize, Clone, PartialEq, Eq)]
+pub struct CacheSettings {
+    /// 缓存有效期，单位为秒，为 0 时不缓存
+    pub ttl_secs: u64,
+    /// 最多缓存的条目数
+    pub capacity: usize,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 300,
+            capacity: 10_000,
+        }
+    }
+}
+
+/// 合作方 API 密钥配置
+#[derive(Deserialize, Clone)]
+pub struct ApiKeySettings {
+    /// 为 true 时转链接口必须携带 API 密钥，否则只校验携带了的密钥
+    #[serde(default)]
+    pub required: bool,
+    /// 签发密钥时未指定限额使用的每分钟请求数
+    pub default_rate_limit_per_minute: u32,
+    /// 签发密钥时未指定限额使用的每日请求数，按 UTC 日期计算
+    pub default_daily_quota: u64,
+    /// 被拒绝的请求数写入数据库的间隔，单位为秒
+    #[serde(default = "default_rejection_flush_interval_secs")]
+    pub rejection_flush_interval_secs: u64,
+}
+
+fn default_rejection_flush_interval_secs() -> u64 {
+    10
+}
+
+impl Default for ApiKeySettings {
+    fn default() -> Self {
+        Self {
+            required: false,
+            default_rate_limit_per_minute: 60,
+            default_daily_quota: 10_000,
+            rejection_flush_interval_secs: default_rejection_flush_interval_secs(),
+        }
+    }
 }
@@ -25,9 +25,10 @@ impl DatabaseSettings {
             .acquire_timeout(Duration::from_secs(8))
             .idle_timeout(Duration::from_secs(8))
             .max_lifetime(Duration::from_secs(8))
+            // 启动时不建立连接，数据库不可用时查询返回错误而不是阻塞启动
+            .connect_lazy(true)
             .sqlx_logging(false)
             .sqlx_logging_level(LevelFilter::Error)
-            .set_schema_search_path("./schema")
             .to_owned()
     }
 }
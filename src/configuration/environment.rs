@@ -1,9 +1,39 @@
-use strum::{AsRefStr, Display, EnumString, IntoStaticStr};
+use strum::{AsRefStr, Display, EnumString, IntoStaticStr, VariantNames};
 
-#[derive(IntoStaticStr, AsRefStr, EnumString, Default, Display)]
-#[strum(serialize_all = "snake_case")]
+/// 运行环境，对应配置目录中的 `{环境}.yaml`
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    IntoStaticStr,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    Default,
+    Display,
+)]
+#[strum(
+    serialize_all = "snake_case",
+    parse_err_fn = unknown_environment,
+    parse_err_ty = UnknownEnvironment
+)]
 pub enum Environment {
     #[default]
     Local,
+    /// 自动化测试
+    Test,
+    /// 预发布
+    Staging,
     Production,
 }
+
+/// 未知的运行环境
+#[derive(thiserror::Error, Debug)]
+#[error("未知的运行环境 \"{0}\"，可选值为 {choices}", choices = Environment::VARIANTS.join("、"))]
+pub struct UnknownEnvironment(String);
+
+fn unknown_environment(value: &str) -> UnknownEnvironment {
+    UnknownEnvironment(value.to_string())
+}
@@ -8,10 +8,42 @@ use tracing_appender::rolling::Rotation;
 pub struct LogSettings {
     pub log_dir: String,
     pub targets: Vec<Target>,
+    /// 通过 OTLP 导出链路追踪数据，不配置时不导出
+    #[serde(default)]
+    pub otlp: Option<OtlpSettings>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OtlpSettings {
+    /// 采集器地址，如 gRPC `http://127.0.0.1:4317`、HTTP `http://127.0.0.1:4318/v1/traces`
+    pub endpoint: String,
+    #[serde(default)]
+    pub protocol: OtlpProtocol,
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    #[serde(default = "default_service_version")]
+    pub service_version: String,
+    /// 采样比例，取值 0.0-1.0
+    #[serde(default = "default_sampling_ratio")]
+    pub sampling_ratio: f64,
+    /// 导出 span 的 EnvFilter 指令
+    #[serde(default = "default_otlp_filter")]
+    pub filter: String,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum OtlpProtocol {
+    #[default]
+    Grpc,
+    Http,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Target {
+    /// 输出目标名称，默认 stdout 为 `stdout`，文件为文件名
+    #[serde(default)]
+    pub name: Option<String>,
     #[serde(default)]
     pub kind: TargetKind,
     #[serde(default = "FilenameString::default_filename")]
@@ -22,6 +54,49 @@ pub struct Target {
     #[serde(with = "RotationDef")]
     #[serde(default = "default_rotation")]
     pub rotation: Rotation,
+    #[serde(default)]
+    pub format: LogFormat,
+    /// EnvFilter 指令，如 `info,kuai_saver=debug`，设置后忽略 `level`
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// 最多保留的日志文件数，仅对文件输出有效
+    #[serde(default)]
+    pub max_files: Option<usize>,
+    /// 日志文件总大小上限，单位为 MB，仅对文件输出有效
+    #[serde(default)]
+    pub max_total_size_mb: Option<u64>,
+    /// 日志文件最长保留天数，按文件修改时间计算，仅对文件输出有效
+    #[serde(default)]
+    pub max_age_days: Option<u64>,
+    /// 是否 gzip 压缩已轮转的日志文件，仅对文件输出有效
+    #[serde(default)]
+    pub compress: bool,
+}
+
+impl Target {
+    /// 是否需要执行日志保留策略
+    pub fn has_retention(&self) -> bool {
+        matches!(self.kind, TargetKind::File)
+            && (self.max_files.is_some()
+                || self.max_total_size_mb.is_some()
+                || self.max_age_days.is_some()
+                || self.compress)
+    }
+
+    pub fn name(&self) -> String {
+        match (&self.name, self.kind) {
+            (Some(name), _) => name.clone(),
+            (None, TargetKind::Stdout) => "stdout".to_string(),
+            (None, TargetKind::File) => self.filename.to_string(),
+        }
+    }
+
+    /// 该输出目标使用的过滤指令
+    pub fn directive(&self) -> String {
+        self.filter
+            .clone()
+            .unwrap_or_else(|| self.level.as_str().to_lowercase())
+    }
 }
 
 #[derive(Serialize, Deserialize, Default, Clone)]
@@ -55,6 +130,15 @@ pub enum TargetKind {
     File,
 }
 
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Json,
+    #[default]
+    Compact,
+    Pretty,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct FilenameString(String);
 
@@ -79,3 +163,19 @@ pub fn default_level() -> Level {
 pub fn default_rotation() -> Rotation {
     Rotation::DAILY
 }
+
+fn default_service_name() -> String {
+    env!("CARGO_PKG_NAME").to_string()
+}
+
+fn default_service_version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+fn default_sampling_ratio() -> f64 {
+    1.0
+}
+
+fn default_otlp_filter() -> String {
+    "info".to_string()
+}
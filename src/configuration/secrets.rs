@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, anyhow, bail};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use ring::{
+    aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey},
+    rand::{SecureRandom, SystemRandom},
+};
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+
+/// 所有密钥类配置项，都支持通过 `<配置项>_file` 从文件读取
+///
+/// `[]` 表示数组中的每个元素，如 `application.admin.operators[].token` 对应
+/// `application.admin.operators[0].token`、`application.admin.operators[1].token` 等
+pub const SECRET_PATHS: [&str; 7] = [
+    "application.pdd.client_id",
+    "application.pdd.client_secret",
+    "application.pdd.pid",
+    "application.admin.token",
+    "application.admin.operators[].token",
+    "application.price_alert.notifier.password",
+    "db.password",
+];
+
+/// 密钥来源配置
+#[derive(Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SecretsSettings {
+    /// 本地 AES-256-GCM 加密文件，见 [`EncryptedFileProvider`]
+    EncryptedFile {
+        path: String,
+        /// 保存十六进制 32 字节密钥的文件
+        key_file: String,
+    },
+}
+
+/// 密钥提供者，按配置项路径查询密钥
+pub trait SecretProvider: Send + Sync {
+    /// 没有该配置项时返回 `None`
+    fn get(&self, path: &str) -> anyhow::Result<Option<SecretString>>;
+}
+
+pub fn build_provider(settings: &SecretsSettings) -> anyhow::Result<Box<dyn SecretProvider>> {
+    match settings {
+        SecretsSettings::EncryptedFile { path, key_file } => {
+            Ok(Box::new(EncryptedFileProvider::open(path, key_file)?))
+        }
+    }
+}
+
+/// 填充密钥类配置项
+///
+/// 每个配置项按以下顺序取值：`<配置项>_file` 指向的文件、配置中的明文值、
+/// `secrets` 配置的密钥提供者。文件内容末尾的换行会被去掉
+pub fn resolve(raw: config::Config) -> anyhow::Result<config::Config> {
+    let provider = match raw.get::<SecretsSettings>("secrets") {
+        Ok(settings) => Some(build_provider(&settings).context("加载密钥提供者失败")?),
+        Err(config::ConfigError::NotFound(_)) => None,
+        Err(e) => return Err(anyhow!(e).context("secrets 配置无效")),
+    };
+
+    let mut builder = config::Config::builder().add_source(raw.clone());
+    for path in SECRET_PATHS.iter().flat_map(|path| expand_path(&raw, path)) {
+        let path = path.as_str();
+        let file_key = format!("{}_file", path);
+        let value = if let Ok(file) = raw.get_string(&file_key) {
+            Some(
+                read_secret_file(&file)
+                    .with_context(|| format!("{}: 读取密钥文件失败", file_key))?,
+            )
+        } else if raw.get_string(path).is_ok() {
+            None
+        } else {
+            // 只为已配置的部分补充密钥，避免凭空启用管理接口等功能
+            let section_configured = path
+                .rsplit_once('.')
+                .is_some_and(|(parent, _)| raw.get_table(parent).is_ok());
+            match &provider {
+                Some(provider) if section_configured => provider
+                    .get(path)?
+                    .map(|secret| secret.expose_secret().to_string()),
+                _ => None,
+            }
+        };
+        if let Some(value) = value {
+            builder = builder.set_override(path, value)?;
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// 按 `raw` 中数组的实际长度展开路径中的 `[]`
+fn expand_path(raw: &config::Config, path: &str) -> Vec<String> {
+    match path.split_once("[]") {
+        Some((array, rest)) => {
+            let len = raw.get_array(array).map_or(0, |items| items.len());
+            (0..len)
+                .map(|i| format!("{}[{}]{}", array, i, rest))
+                .collect()
+        }
+        None => vec![path.to_string()],
+    }
+}
+
+fn read_secret_file(path: &str) -> anyhow::Result<String> {
+    let content = std::fs::read_to_string(path).with_context(|| path.to_string())?;
+    Ok(content.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// 本地加密文件中的密钥
+///
+/// 文件内容为 base64 编码的 `nonce || 密文`，明文是配置项路径到密钥的 JSON 对象，
+/// 如 `{"application.pdd.client_secret": "..."}`。加密文件可以提交到仓库，解密用的
+/// 密钥文件单独下发
+pub struct EncryptedFileProvider {
+    secrets: BTreeMap<String, SecretString>,
+}
+
+impl EncryptedFileProvider {
+    pub fn open(path: &str, key_file: &str) -> anyhow::Result<Self> {
+        let key = read_key(key_file)?;
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("读取加密文件 {} 失败", path))?;
+        let mut sealed = STANDARD
+            .decode(content.trim())
+            .with_context(|| format!("加密文件 {} 不是有效的 base64", path))?;
+        if sealed.len() < NONCE_LEN {
+            bail!("加密文件 {} 内容过短", path);
+        }
+        let mut ciphertext = sealed.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&sealed).map_err(|_| anyhow!("nonce 无效"))?;
+        let plaintext = key
+            .open_in_place(nonce, Aad::empty(), &mut ciphertext)
+            .map_err(|_| anyhow!("解密 {} 失败，密钥不匹配或文件已损坏", path))?;
+        let secrets: BTreeMap<String, String> = serde_json::from_slice(plaintext)
+            .with_context(|| format!("加密文件 {} 的内容不是 JSON 对象", path))?;
+
+        Ok(Self {
+            secrets: secrets
+                .into_iter()
+                .map(|(k, v)| (k, SecretString::from(v)))
+                .collect(),
+        })
+    }
+
+    /// 加密密钥，返回可以直接写入加密文件的内容
+    pub fn seal(key_file: &str, secrets: &BTreeMap<String, String>) -> anyhow::Result<String> {
+        let key = read_key(key_file)?;
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| anyhow!("生成 nonce 失败"))?;
+
+        let mut buffer = serde_json::to_vec(secrets)?;
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut buffer,
+        )
+        .map_err(|_| anyhow!("加密失败"))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&buffer);
+        Ok(STANDARD.encode(sealed))
+    }
+}
+
+impl SecretProvider for EncryptedFileProvider {
+    fn get(&self, path: &str) -> anyhow::Result<Option<SecretString>> {
+        Ok(self.secrets.get(path).cloned())
+    }
+}
+
+fn read_key(key_file: &str) -> anyhow::Result<LessSafeKey> {
+    let hex_key = read_secret_file(key_file).context("读取解密密钥失败")?;
+    let bytes = hex::decode(hex_key.trim()).context("解密密钥不是有效的十六进制")?;
+    let key = UnboundKey::new(&AES_256_GCM, &bytes)
+        .map_err(|_| anyhow!("解密密钥长度应为 32 字节，实际为 {} 字节", bytes.len()))?;
+    Ok(LessSafeKey::new(key))
+}
@@ -0,0 +1,344 @@
+use std::{
+    fmt::Display,
+    fs::OpenOptions,
+    path::{Path, PathBuf},
+};
+
+use lettre::message::Mailbox;
+use reqwest::Url;
+use secrecy::{ExposeSecret, SecretString};
+use thiserror::Error;
+use tracing_subscriber::EnvFilter;
+
+use crate::configuration::{
+    ApplicationSettings, DatabaseSettings, LogSettings, Settings,
+    application::{FixtureMode, NotifierSettings},
+    logging::TargetKind,
+};
+
+/// 按天计的保留、追踪时长上限，约十年
+const MAX_DAYS: u64 = 3650;
+
+/// 配置校验未通过，包含所有发现的问题
+#[derive(Error, Debug)]
+#[error("{}", format_problems(.problems))]
+pub struct ConfigError {
+    pub problems: Vec<ConfigProblem>,
+}
+
+/// 单个配置项的问题
+#[derive(Debug, Clone)]
+pub struct ConfigProblem {
+    /// 配置项路径，如 `application.pdd.client_id`
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+fn format_problems(problems: &[ConfigProblem]) -> String {
+    let mut report = format!("配置校验未通过，共 {} 个问题:", problems.len());
+    for problem in problems {
+        report.push_str(&format!("\n  - {}", problem));
+    }
+    report
+}
+
+impl Settings {
+    /// 检查配置取值是否可用，一次返回全部问题
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut v = Validator::default();
+        validate_application(&mut v, &self.application);
+        validate_log(&mut v, &self.log);
+        validate_database(&mut v, &self.db);
+        v.finish()
+    }
+}
+
+#[derive(Default)]
+struct Validator {
+    problems: Vec<ConfigProblem>,
+}
+
+impl Validator {
+    fn problem(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.problems.push(ConfigProblem {
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    fn check(&mut self, ok: bool, path: &str, message: &str) {
+        if !ok {
+            self.problem(path, message);
+        }
+    }
+
+    fn not_empty(&mut self, path: &str, value: &str) {
+        self.check(!value.trim().is_empty(), path, "不能为空");
+    }
+
+    fn secret_not_empty(&mut self, path: &str, value: &SecretString) {
+        self.not_empty(path, value.expose_secret());
+    }
+
+    fn positive<T: Default + PartialOrd>(&mut self, path: &str, value: T) {
+        self.check(value > T::default(), path, "必须大于 0");
+    }
+
+    fn days(&mut self, path: &str, value: u64) {
+        if value == 0 {
+            self.problem(path, "必须大于 0");
+        } else if value > MAX_DAYS {
+            self.problem(path, format!("不能超过 {} 天", MAX_DAYS));
+        }
+    }
+
+    fn http_url(&mut self, path: &str, value: &str) {
+        match Url::parse(value) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            Ok(url) => self.problem(path, format!("只支持 http/https，实际为 {}", url.scheme())),
+            Err(e) => self.problem(path, format!("不是有效的 URL \"{}\": {}", value, e)),
+        }
+    }
+
+    fn readable_file(&mut self, path: &str, value: &str) {
+        if let Err(e) = std::fs::File::open(value) {
+            self.problem(path, format!("无法读取文件 {}: {}", value, e));
+        }
+    }
+
+    fn env_filter(&mut self, path: &str, directive: &str) {
+        if let Err(e) = EnvFilter::builder().parse(directive) {
+            self.problem(path, format!("过滤指令无效 \"{}\": {}", directive, e));
+        }
+    }
+
+    fn finish(self) -> Result<(), ConfigError> {
+        if self.problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError {
+                problems: self.problems,
+            })
+        }
+    }
+}
+
+fn validate_application(v: &mut Validator, settings: &ApplicationSettings) {
+    match &settings.unix_socket {
+        Some(socket) => {
+            v.not_empty("application.unix_socket", socket);
+            v.check(
+                settings.tls.is_none(),
+                "application.tls",
+                "不能与 unix_socket 同时配置",
+            );
+        }
+        None => {
+            v.not_empty("application.host", &settings.host);
+            v.check(
+                settings.port != 0,
+                "application.port",
+                "不能为 0，随机端口只用于测试",
+            );
+        }
+    }
+    if let Some(tls) = &settings.tls {
+        v.readable_file("application.tls.cert_path", &tls.cert_path);
+        v.readable_file("application.tls.key_path", &tls.key_path);
+    }
+
+    let pdd = &settings.pdd;
+    v.secret_not_empty("application.pdd.client_id", &pdd.client_id);
+    v.secret_not_empty("application.pdd.client_secret", &pdd.client_secret);
+    v.secret_not_empty("application.pdd.pid", &pdd.pid);
+    v.http_url("application.pdd.domain", &pdd.domain);
+    for (path, api) in [
+        ("application.pdd.api_good_search", &pdd.api_good_search),
+        ("application.pdd.api_gen_short_url", &pdd.api_gen_short_url),
+        (
+            "application.pdd.api_goods_recommend",
+            &pdd.api_goods_recommend,
+        ),
+        ("application.pdd.api_top_goods", &pdd.api_top_goods),
+        (
+            "application.pdd.api_promotion_url_generate",
+            &pdd.api_promotion_url_generate,
+        ),
+        (
+            "application.pdd.api_order_list_increment",
+            &pdd.api_order_list_increment,
+        ),
+        ("application.pdd.api_order_detail", &pdd.api_order_detail),
+    ] {
+        v.not_empty(path, api);
+    }
+    if let Some(fixtures) = &pdd.fixtures {
+        v.not_empty("application.pdd.fixtures.dir", &fixtures.dir);
+        if fixtures.mode == FixtureMode::Replay {
+            v.check(
+                Path::new(&fixtures.dir).is_dir(),
+                "application.pdd.fixtures.dir",
+                "回放模式下录制目录必须存在",
+            );
+        }
+    }
+
+    let history = &settings.price_history;
+    v.positive(
+        "application.price_history.refresh_interval_secs",
+        history.refresh_interval_secs,
+    );
+    match u64::try_from(history.track_days) {
+        Ok(days) => v.days("application.price_history.track_days", days),
+        Err(_) => v.problem("application.price_history.track_days", "必须大于 0"),
+    }
+    v.positive("application.price_history.batch_size", history.batch_size);
+
+    let alert = &settings.price_alert;
+    v.positive(
+        "application.price_alert.check_interval_secs",
+        alert.check_interval_secs,
+    );
+    v.positive("application.price_alert.batch_size", alert.batch_size);
+    v.positive(
+        "application.price_alert.max_delivery_attempts",
+        alert.max_delivery_attempts,
+    );
+    match &alert.notifier {
+        NotifierSettings::Log => {}
+        NotifierSettings::File { path } => {
+            v.not_empty("application.price_alert.notifier.path", path);
+            if let Some(parent) = Path::new(path).parent() {
+                writable_dir(v, "application.price_alert.notifier.path", parent);
+            }
+        }
+        NotifierSettings::Smtp(smtp) => {
+            v.not_empty("application.price_alert.notifier.host", &smtp.host);
+            v.not_empty("application.price_alert.notifier.username", &smtp.username);
+            v.secret_not_empty("application.price_alert.notifier.password", &smtp.password);
+            if let Err(e) = smtp.from.parse::<Mailbox>() {
+                v.problem(
+                    "application.price_alert.notifier.from",
+                    format!("不是有效的发件人 \"{}\": {}", smtp.from, e),
+                );
+            }
+        }
+    }
+
+    let api_keys = &settings.api_keys;
+    v.positive(
+        "application.api_keys.default_rate_limit_per_minute",
+        api_keys.default_rate_limit_per_minute,
+    );
+    v.positive(
+        "application.api_keys.default_daily_quota",
+        api_keys.default_daily_quota,
+    );
+    v.positive(
+        "application.api_keys.rejection_flush_interval_secs",
+        api_keys.rejection_flush_interval_secs,
+    );
+
+    if let Some(admin) = &settings.admin {
+        v.secret_not_empty("application.admin.token", &admin.token);
+        let mut names = Vec::new();
+        for (i, operator) in admin.operators.iter().enumerate() {
+            let path = format!("application.admin.operators[{}]", i);
+            v.not_empty(&format!("{}.name", path), &operator.name);
+            v.secret_not_empty(&format!("{}.token", path), &operator.token);
+            if names.contains(&&operator.name) {
+                v.problem(
+                    format!("{}.name", path),
+                    format!("运营人员名称 {} 重复", operator.name),
+                );
+            }
+            names.push(&operator.name);
+        }
+    }
+}
+
+fn validate_log(v: &mut Validator, settings: &LogSettings) {
+    let mut names = Vec::new();
+    for (i, target) in settings.targets.iter().enumerate() {
+        let path = format!("log.targets[{}]", i);
+        let name = target.name();
+        if names.contains(&name) {
+            v.problem(
+                format!("{}.name", path),
+                format!("输出目标名称 {} 重复", name),
+            );
+        }
+        names.push(name);
+
+        v.env_filter(&format!("{}.filter", path), &target.directive());
+        if target.max_files == Some(0) {
+            v.problem(format!("{}.max_files", path), "必须大于 0");
+        }
+        if target.max_total_size_mb == Some(0) {
+            v.problem(format!("{}.max_total_size_mb", path), "必须大于 0");
+        }
+        if let Some(days) = target.max_age_days {
+            v.days(&format!("{}.max_age_days", path), days);
+        }
+    }
+
+    if settings
+        .targets
+        .iter()
+        .any(|t| matches!(t.kind, TargetKind::File))
+    {
+        v.not_empty("log.log_dir", &settings.log_dir);
+        if !settings.log_dir.trim().is_empty() {
+            writable_dir(v, "log.log_dir", Path::new(&settings.log_dir));
+        }
+    }
+
+    if let Some(otlp) = &settings.otlp {
+        v.http_url("log.otlp.endpoint", &otlp.endpoint);
+        v.check(
+            (0.0..=1.0).contains(&otlp.sampling_ratio),
+            "log.otlp.sampling_ratio",
+            "取值范围为 0.0-1.0",
+        );
+        v.env_filter("log.otlp.filter", &otlp.filter);
+    }
+}
+
+fn validate_database(v: &mut Validator, settings: &DatabaseSettings) {
+    v.not_empty("db.host", &settings.host);
+    v.check(settings.port != 0, "db.port", "不能为 0");
+    v.not_empty("db.database", &settings.database);
+    v.not_empty("db.username", &settings.username);
+}
+
+/// 检查目录可写；目录不存在时检查能否在最近的已有上级目录中创建
+fn writable_dir(v: &mut Validator, path: &str, dir: &Path) {
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let Some(existing) = dir.ancestors().find(|p| p.exists()) else {
+        v.problem(path, format!("目录 {} 不存在", dir.display()));
+        return;
+    };
+    if !existing.is_dir() {
+        v.problem(path, format!("{} 不是目录", existing.display()));
+        return;
+    }
+
+    // 权限位不能反映只读挂载等情况，直接尝试创建文件
+    let probe: PathBuf = existing.join(format!(".write-check-{}", std::process::id()));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+        }
+        Err(e) => v.problem(path, format!("目录 {} 不可写: {}", existing.display(), e)),
+    }
+}
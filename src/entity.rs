@@ -1 +1,7 @@
-mod subscriptions;
+pub mod alert_deliveries;
+pub mod api_key_usage;
+pub mod api_keys;
+pub mod audit_log;
+pub mod orders;
+pub mod price_history;
+pub mod subscriptions;
@@ -0,0 +1,57 @@
+// CREATE TABLE alert_deliveries (
+//     id BIGSERIAL NOT NULL,
+//     PRIMARY KEY (id),
+//     subscription_id uuid NOT NULL REFERENCES subscriptions (id) ON DELETE CASCADE,
+//     channel TEXT NOT NULL,
+//     price BIGINT NOT NULL,
+//     success BOOLEAN NOT NULL,
+//     error TEXT,
+//     delivered_at TIMESTAMPTZ NOT NULL
+// );
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ConnectionTrait, DbErr, entity::prelude::*};
+
+use crate::money::Money;
+
+/// 降价提醒的发送记录
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "alert_deliveries")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub subscription_id: Uuid,
+    /// 发送渠道，如 smtp、log
+    pub channel: String,
+    /// 触发提醒时的券后价，单位为分
+    pub price: i64,
+    pub success: bool,
+    pub error: Option<String>,
+    pub delivered_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 记录一次发送结果
+pub async fn record<C: ConnectionTrait>(
+    db: &C,
+    subscription_id: Uuid,
+    channel: &str,
+    price: Money,
+    error: Option<String>,
+) -> Result<Model, DbErr> {
+    ActiveModel {
+        subscription_id: Set(subscription_id),
+        channel: Set(channel.to_string()),
+        price: Set(price.cents()),
+        success: Set(error.is_none()),
+        error: Set(error),
+        delivered_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
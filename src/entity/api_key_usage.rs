@@ -0,0 +1,85 @@
+// CREATE TABLE api_key_usage (
+//     api_key_id uuid NOT NULL REFERENCES api_keys (id) ON DELETE CASCADE,
+//     day DATE NOT NULL,
+//     PRIMARY KEY (api_key_id, day),
+//     requests BIGINT NOT NULL,
+//     rejected BIGINT NOT NULL
+// );
+
+use chrono::NaiveDate;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, Statement,
+    entity::prelude::*,
+};
+
+/// API 密钥每天的调用次数，日期按 UTC 计算
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "api_key_usage")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub api_key_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub day: NaiveDate,
+    /// 计入配额的请求数
+    pub requests: i64,
+    /// 因超出配额或频率限制被拒绝的请求数
+    pub rejected: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 当日请求数未达到配额时计数一次并返回 true，否则返回 false
+///
+/// 判断和计数在同一条语句中完成，多个实例同时处理请求时也不会超出配额
+pub async fn try_consume<C: ConnectionTrait>(
+    db: &C,
+    api_key_id: Uuid,
+    day: NaiveDate,
+    daily_quota: i64,
+) -> Result<bool, DbErr> {
+    let row = db
+        .query_one(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "INSERT INTO api_key_usage (api_key_id, day, requests, rejected) VALUES ($1, $2, 1, 0)
+             ON CONFLICT (api_key_id, day) DO UPDATE SET requests = api_key_usage.requests + 1
+             WHERE api_key_usage.requests < $3
+             RETURNING requests",
+            [api_key_id.into(), day.into(), daily_quota.into()],
+        ))
+        .await?;
+    Ok(row.is_some())
+}
+
+/// 累加被拒绝的请求数
+pub async fn record_rejected<C: ConnectionTrait>(
+    db: &C,
+    api_key_id: Uuid,
+    day: NaiveDate,
+    count: i64,
+) -> Result<(), DbErr> {
+    db.execute(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        "INSERT INTO api_key_usage (api_key_id, day, requests, rejected) VALUES ($1, $2, 0, $3)
+         ON CONFLICT (api_key_id, day) DO UPDATE SET rejected = api_key_usage.rejected + $3",
+        [api_key_id.into(), day.into(), count.into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// 按日期倒序查询 `since` 之后（含）的用量
+pub async fn find_since<C: ConnectionTrait>(
+    db: &C,
+    api_key_id: Uuid,
+    since: NaiveDate,
+) -> Result<Vec<Model>, DbErr> {
+    Entity::find()
+        .filter(Column::ApiKeyId.eq(api_key_id))
+        .filter(Column::Day.gte(since))
+        .order_by_desc(Column::Day)
+        .all(db)
+        .await
+}
@@ -0,0 +1,124 @@
+// CREATE TABLE api_keys (
+//     id uuid NOT NULL,
+//     PRIMARY KEY (id),
+//     name TEXT NOT NULL,
+//     owner TEXT NOT NULL,
+//     pid TEXT,
+//     key_prefix TEXT NOT NULL,
+//     key_hash TEXT NOT NULL UNIQUE,
+//     scopes TEXT NOT NULL,
+//     rate_limit_per_minute INTEGER NOT NULL,
+//     daily_quota BIGINT NOT NULL,
+//     created_at TIMESTAMPTZ NOT NULL,
+//     revoked_at TIMESTAMPTZ
+// );
+// CREATE INDEX api_keys_owner_idx ON api_keys (owner);
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, entity::prelude::*,
+};
+
+/// 合作方 API 密钥，只保存密钥的 SHA-256 摘要
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "api_keys")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub name: String,
+    /// 所属合作方
+    pub owner: String,
+    /// 合作方自己的推广位，转链时代替默认推广位
+    pub pid: Option<String>,
+    /// 密钥开头几位，用于展示和辨认
+    pub key_prefix: String,
+    pub key_hash: String,
+    /// 空格分隔的授权范围
+    pub scopes: String,
+    pub rate_limit_per_minute: i32,
+    pub daily_quota: i64,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 新建密钥的参数
+#[derive(Debug, Clone)]
+pub struct NewApiKey {
+    pub name: String,
+    pub owner: String,
+    pub pid: Option<String>,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: i32,
+    pub daily_quota: i64,
+}
+
+/// 保存新签发的密钥
+pub async fn create<C: ConnectionTrait>(db: &C, key: NewApiKey) -> Result<Model, DbErr> {
+    ActiveModel {
+        id: Set(Uuid::new_v4()),
+        name: Set(key.name),
+        owner: Set(key.owner),
+        pid: Set(key.pid),
+        key_prefix: Set(key.key_prefix),
+        key_hash: Set(key.key_hash),
+        scopes: Set(key.scopes.join(" ")),
+        rate_limit_per_minute: Set(key.rate_limit_per_minute),
+        daily_quota: Set(key.daily_quota),
+        created_at: Set(Utc::now()),
+        revoked_at: Set(None),
+    }
+    .insert(db)
+    .await
+}
+
+/// 按摘要查询未吊销的密钥
+pub async fn find_active_by_hash<C: ConnectionTrait>(
+    db: &C,
+    key_hash: &str,
+) -> Result<Option<Model>, DbErr> {
+    Entity::find()
+        .filter(Column::KeyHash.eq(key_hash))
+        .filter(Column::RevokedAt.is_null())
+        .one(db)
+        .await
+}
+
+pub async fn find<C: ConnectionTrait>(db: &C, id: Uuid) -> Result<Option<Model>, DbErr> {
+    Entity::find_by_id(id).one(db).await
+}
+
+/// 按签发时间倒序分页查询密钥，页码从 1 开始，返回当前页和总数
+pub async fn find_page<C: ConnectionTrait>(
+    db: &C,
+    owner: Option<&str>,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<Model>, u64), DbErr> {
+    let mut query = Entity::find().order_by_desc(Column::CreatedAt);
+    if let Some(owner) = owner {
+        query = query.filter(Column::Owner.eq(owner));
+    }
+
+    let paginator = query.paginate(db, page_size);
+    let total = paginator.num_items().await?;
+    let items = paginator.fetch_page(page.saturating_sub(1)).await?;
+    Ok((items, total))
+}
+
+/// 吊销密钥，已吊销的密钥保持原吊销时间
+pub async fn revoke<C: ConnectionTrait>(db: &C, model: Model) -> Result<Model, DbErr> {
+    if model.revoked_at.is_some() {
+        return Ok(model);
+    }
+    let mut active: ActiveModel = model.into();
+    active.revoked_at = Set(Some(Utc::now()));
+    active.update(db).await
+}
@@ -0,0 +1,138 @@
+// CREATE TABLE audit_log (
+//     id BIGSERIAL NOT NULL,
+//     PRIMARY KEY (id),
+//     actor TEXT NOT NULL,
+//     action TEXT NOT NULL,
+//     target TEXT NOT NULL,
+//     before JSONB,
+//     after JSONB,
+//     request_id TEXT,
+//     created_at TIMESTAMPTZ NOT NULL
+// );
+// CREATE INDEX audit_log_actor_idx ON audit_log (actor, created_at);
+// CREATE INDEX audit_log_target_idx ON audit_log (target, created_at);
+// -- 只允许追加，修改、删除和清空都报错
+// CREATE FUNCTION audit_log_append_only() RETURNS trigger LANGUAGE plpgsql AS $$
+// BEGIN
+//     RAISE EXCEPTION 'audit_log 只允许追加，不能执行 %', TG_OP;
+// END;
+// $$;
+// CREATE TRIGGER audit_log_no_modify BEFORE UPDATE OR DELETE ON audit_log
+//     FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
+// CREATE TRIGGER audit_log_no_truncate BEFORE TRUNCATE ON audit_log
+//     FOR EACH STATEMENT EXECUTE FUNCTION audit_log_append_only();
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, entity::prelude::*,
+};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::middleware::current_request_id;
+
+/// 特权操作和资金类操作的审计记录，只追加不修改
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// 操作者，管理接口为运营人员名称，后台任务为 `system`
+    pub actor: String,
+    /// 操作类型，如 `order.resync`
+    pub action: String,
+    /// 操作对象，如 `order:pdd:200101-1`
+    pub target: String,
+    /// 操作前的状态，新建时为空
+    pub before: Option<Value>,
+    /// 操作后的状态，删除时为空
+    pub after: Option<Value>,
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 一次待记录的操作
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub actor: String,
+    pub action: &'static str,
+    pub target: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// 审计记录查询条件
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub target: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// 写入审计记录，在请求上下文中时自动带上请求 id
+///
+/// 需要与被审计的修改保持一致时，传入同一个事务
+pub async fn record<C: ConnectionTrait>(db: &C, entry: AuditEntry) -> Result<Model, DbErr> {
+    ActiveModel {
+        actor: Set(entry.actor),
+        action: Set(entry.action.to_string()),
+        target: Set(entry.target),
+        before: Set(entry.before),
+        after: Set(entry.after),
+        request_id: Set(current_request_id()),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
+
+/// 按时间倒序分页查询审计记录，页码从 1 开始，返回当前页和总数
+pub async fn find_page<C: ConnectionTrait>(
+    db: &C,
+    filter: &AuditFilter,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<Model>, u64), DbErr> {
+    let mut query = Entity::find().order_by_desc(Column::CreatedAt);
+    if let Some(actor) = &filter.actor {
+        query = query.filter(Column::Actor.eq(actor));
+    }
+    if let Some(target) = &filter.target {
+        query = query.filter(Column::Target.eq(target));
+    }
+    if let Some(since) = filter.since {
+        query = query.filter(Column::CreatedAt.gte(since));
+    }
+    if let Some(until) = filter.until {
+        query = query.filter(Column::CreatedAt.lt(until));
+    }
+
+    let paginator = query.paginate(db, page_size);
+    let total = paginator.num_items().await?;
+    let items = paginator.fetch_page(page.saturating_sub(1)).await?;
+    Ok((items, total))
+}
+
+/// 写入与数据库修改无关的操作（如修改运行时状态）的审计记录，失败时把记录内容
+/// 写入告警日志，不影响已完成的操作
+pub async fn record_or_warn<C: ConnectionTrait>(db: &C, entry: AuditEntry) {
+    if let Err(e) = record(db, entry.clone()).await {
+        warn!(
+            actor = %entry.actor,
+            action = entry.action,
+            target = %entry.target,
+            before = ?entry.before,
+            after = ?entry.after,
+            "写入审计记录失败: {}",
+            e
+        );
+    }
+}
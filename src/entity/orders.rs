@@ -0,0 +1,126 @@
+// CREATE TABLE orders (
+//     platform TEXT NOT NULL,
+//     order_sn TEXT NOT NULL,
+//     PRIMARY KEY (platform, order_sn),
+//     goods_id TEXT NOT NULL,
+//     goods_name TEXT NOT NULL,
+//     pid TEXT NOT NULL,
+//     order_amount BIGINT NOT NULL,
+//     promotion_rate BIGINT NOT NULL,
+//     promotion_amount BIGINT NOT NULL,
+//     status INTEGER NOT NULL,
+//     created_at TIMESTAMPTZ NOT NULL,
+//     modified_at TIMESTAMPTZ NOT NULL,
+//     synced_at TIMESTAMPTZ NOT NULL
+// );
+// CREATE INDEX orders_modified_idx ON orders (modified_at);
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    entity::prelude::*, sea_query::OnConflict,
+};
+
+use crate::{Platform, route::translate::OrderInfo};
+
+/// 推广订单，金额单位为分，佣金比例为千分比
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "orders")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub platform: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub order_sn: String,
+    pub goods_id: String,
+    pub goods_name: String,
+    pub pid: String,
+    pub order_amount: i64,
+    pub promotion_rate: i64,
+    pub promotion_amount: i64,
+    /// 平台定义的订单状态
+    pub status: i32,
+    pub created_at: DateTime<Utc>,
+    pub modified_at: DateTime<Utc>,
+    /// 最近一次同步时间
+    pub synced_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 写入订单，已存在时更新为最新状态
+pub async fn upsert<C: ConnectionTrait>(
+    db: &C,
+    platform: Platform,
+    order: &OrderInfo,
+) -> Result<Model, DbErr> {
+    let timestamp = |secs: i64| DateTime::from_timestamp(secs, 0).unwrap_or_default();
+    let model = ActiveModel {
+        platform: Set(platform.to_string()),
+        order_sn: Set(order.order_sn.clone()),
+        goods_id: Set(order.goods_id.clone()),
+        goods_name: Set(order.goods_name.clone()),
+        pid: Set(order.pid.clone()),
+        order_amount: Set(order.order_amount.cents()),
+        promotion_rate: Set(order.promotion_rate.permille()),
+        promotion_amount: Set(order.promotion_amount.cents()),
+        status: Set(order.status),
+        created_at: Set(timestamp(order.created_at)),
+        modified_at: Set(timestamp(order.modified_at)),
+        synced_at: Set(Utc::now()),
+    };
+
+    Entity::insert(model)
+        .on_conflict(
+            OnConflict::columns([Column::Platform, Column::OrderSn])
+                .update_columns([
+                    Column::GoodsId,
+                    Column::GoodsName,
+                    Column::Pid,
+                    Column::OrderAmount,
+                    Column::PromotionRate,
+                    Column::PromotionAmount,
+                    Column::Status,
+                    Column::ModifiedAt,
+                    Column::SyncedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await
+}
+
+/// 按平台和订单号查询订单
+pub async fn find<C: ConnectionTrait>(
+    db: &C,
+    platform: Platform,
+    order_sn: &str,
+) -> Result<Option<Model>, DbErr> {
+    Entity::find_by_id((platform.to_string(), order_sn.to_string()))
+        .one(db)
+        .await
+}
+
+/// 按更新时间倒序分页查询订单，页码从 1 开始，返回当前页和总数
+pub async fn find_page<C: ConnectionTrait>(
+    db: &C,
+    pid: Option<&str>,
+    status: Option<i32>,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<Model>, u64), DbErr> {
+    let mut query = Entity::find().order_by_desc(Column::ModifiedAt);
+    if let Some(pid) = pid {
+        query = query.filter(Column::Pid.eq(pid));
+    }
+    if let Some(status) = status {
+        query = query.filter(Column::Status.eq(status));
+    }
+
+    let paginator = query.paginate(db, page_size);
+    let total = paginator.num_items().await?;
+    let items = paginator.fetch_page(page.saturating_sub(1)).await?;
+    Ok((items, total))
+}
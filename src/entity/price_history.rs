@@ -0,0 +1,129 @@
+// CREATE TABLE price_history (
+//     id BIGSERIAL NOT NULL,
+//     PRIMARY KEY (id),
+//     platform TEXT NOT NULL,
+//     goods_id TEXT NOT NULL,
+//     source_url TEXT NOT NULL,
+//     origin_price BIGINT NOT NULL,
+//     coupon_discount BIGINT NOT NULL,
+//     price BIGINT NOT NULL,
+//     promotion_rate BIGINT NOT NULL,
+//     observed_at TIMESTAMPTZ NOT NULL,
+//     pid TEXT
+// );
+// CREATE INDEX price_history_goods_idx ON price_history (platform, goods_id, observed_at);
+// CREATE INDEX price_history_pid_idx ON price_history (pid, observed_at);
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, entity::prelude::*,
+};
+
+use crate::{Platform, route::translate::GoodInfo};
+
+/// 商品价格观测记录，金额单位为分，佣金比例为千分比
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "price_history")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub platform: String,
+    pub goods_id: String,
+    pub source_url: String,
+    pub origin_price: i64,
+    pub coupon_discount: i64,
+    /// 券后价
+    pub price: i64,
+    pub promotion_rate: i64,
+    pub observed_at: DateTime<Utc>,
+    /// 合作方转链时使用的推广位，使用默认推广位或由后台任务观测时为空
+    pub pid: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 记录一次价格观测
+pub async fn record<C: ConnectionTrait>(
+    db: &C,
+    platform: Platform,
+    source_url: &str,
+    pid: Option<&str>,
+    good_info: &GoodInfo,
+) -> Result<Model, DbErr> {
+    ActiveModel {
+        platform: Set(platform.to_string()),
+        goods_id: Set(good_info.goods_id.clone()),
+        source_url: Set(source_url.to_string()),
+        origin_price: Set(good_info.origin_price.cents()),
+        coupon_discount: Set(good_info.coupon_discount.cents()),
+        price: Set(good_info.coupon_discount_price.cents()),
+        promotion_rate: Set(good_info.promotion_rate.permille()),
+        observed_at: Set(Utc::now()),
+        pid: Set(pid.map(str::to_string)),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
+
+/// 按时间倒序查询商品的价格记录
+pub async fn find_by_goods<C: ConnectionTrait>(
+    db: &C,
+    platform: Platform,
+    goods_id: &str,
+    limit: u64,
+) -> Result<Vec<Model>, DbErr> {
+    Entity::find()
+        .filter(Column::Platform.eq(platform.to_string()))
+        .filter(Column::GoodsId.eq(goods_id))
+        .order_by_desc(Column::ObservedAt)
+        .limit(limit)
+        .all(db)
+        .await
+}
+
+/// 查询指定时间之后被观测过的商品，每个商品只返回最近一次记录
+///
+/// 超过 `limit` 时优先返回最近观测过的商品
+pub async fn find_tracked_since<C: ConnectionTrait>(
+    db: &C,
+    since: DateTime<Utc>,
+    limit: u64,
+) -> Result<Vec<Model>, DbErr> {
+    let latest_ids: Vec<i64> = Entity::find()
+        .select_only()
+        .column_as(Column::Id.max(), "id")
+        .filter(Column::ObservedAt.gte(since))
+        .group_by(Column::Platform)
+        .group_by(Column::GoodsId)
+        .order_by_desc(Column::ObservedAt.max())
+        .limit(limit)
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    Entity::find()
+        .filter(Column::Id.is_in(latest_ids))
+        .all(db)
+        .await
+}
+
+/// 按时间倒序分页查询推广位转过的链接，页码从 1 开始
+pub async fn find_by_pid<C: ConnectionTrait>(
+    db: &C,
+    pid: &str,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<Model>, u64), DbErr> {
+    let paginator = Entity::find()
+        .filter(Column::Pid.eq(pid))
+        .order_by_desc(Column::ObservedAt)
+        .paginate(db, page_size);
+    let total = paginator.num_items().await?;
+    let items = paginator.fetch_page(page.saturating_sub(1)).await?;
+    Ok((items, total))
+}
@@ -1,7 +1,194 @@
 // CREATE TABLE subscriptions (
 //     id uuid NOT NULL,
 //     PRIMARY KEY (id),
-//     email TEXT NOT NULL UNIQUE,
+//     email TEXT NOT NULL,
 //     name TEXT NOT NULL,
-//     subscribed_at TIMESTAMP NOT NULL
+//     platform TEXT NOT NULL,
+//     goods_id TEXT NOT NULL,
+//     target_price BIGINT NOT NULL,
+//     subscribed_at TIMESTAMPTZ NOT NULL,
+//     notified_at TIMESTAMPTZ,
+//     last_checked_at TIMESTAMPTZ,
+//     failed_deliveries INTEGER NOT NULL DEFAULT 0,
+//     api_key_id uuid REFERENCES api_keys (id)
 // );
+// CREATE INDEX subscriptions_pending_idx ON subscriptions (last_checked_at)
+//     WHERE notified_at IS NULL;
+// CREATE INDEX subscriptions_api_key_idx ON subscriptions (api_key_id);
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::Set,
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, Order,
+    QueryFilter, QueryOrder, QuerySelect,
+    entity::prelude::*,
+    sea_query::{Expr, NullOrdering},
+};
+
+use crate::{Platform, money::Money};
+
+/// 降价提醒订阅，目标价单位为分
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "subscriptions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub platform: String,
+    pub goods_id: String,
+    /// 券后价低于该价格时提醒
+    pub target_price: i64,
+    pub subscribed_at: DateTime<Utc>,
+    /// 已提醒的时间，提醒后不再重复发送
+    pub notified_at: Option<DateTime<Utc>>,
+    /// 最近一次被降价检查选中的时间，从未检查过时为空
+    pub last_checked_at: Option<DateTime<Utc>>,
+    /// 连续发送失败的次数
+    pub failed_deliveries: i32,
+    /// 创建订阅的 API 密钥，只有它能删除订阅；早于密钥认证的订阅为空
+    pub api_key_id: Option<Uuid>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 新建订阅
+pub async fn create<C: ConnectionTrait>(
+    db: &C,
+    api_key_id: Uuid,
+    email: &str,
+    name: &str,
+    platform: Platform,
+    goods_id: &str,
+    target_price: Money,
+) -> Result<Model, DbErr> {
+    ActiveModel {
+        id: Set(Uuid::new_v4()),
+        email: Set(email.to_string()),
+        name: Set(name.to_string()),
+        platform: Set(platform.to_string()),
+        goods_id: Set(goods_id.to_string()),
+        target_price: Set(target_price.cents()),
+        subscribed_at: Set(Utc::now()),
+        notified_at: Set(None),
+        last_checked_at: Set(None),
+        failed_deliveries: Set(0),
+        api_key_id: Set(Some(api_key_id)),
+    }
+    .insert(db)
+    .await
+}
+
+/// 删除密钥创建的订阅，返回是否存在
+pub async fn delete<C: ConnectionTrait>(db: &C, api_key_id: Uuid, id: Uuid) -> Result<bool, DbErr> {
+    let res = Entity::delete_many()
+        .filter(Column::Id.eq(id))
+        .filter(Column::ApiKeyId.eq(api_key_id))
+        .exec(db)
+        .await?;
+    Ok(res.rows_affected > 0)
+}
+
+/// 查询尚未提醒的订阅，最久未检查的优先，发送失败达到 `max_failed_deliveries` 次的不再返回
+pub async fn find_pending<C: ConnectionTrait>(
+    db: &C,
+    max_failed_deliveries: i32,
+    limit: u64,
+) -> Result<Vec<Model>, DbErr> {
+    Entity::find()
+        .filter(Column::NotifiedAt.is_null())
+        .filter(Column::FailedDeliveries.lt(max_failed_deliveries))
+        .order_by_with_nulls(Column::LastCheckedAt, Order::Asc, NullOrdering::First)
+        .order_by_asc(Column::SubscribedAt)
+        .limit(limit)
+        .all(db)
+        .await
+}
+
+/// 记录订阅本轮已检查，下一轮优先检查其他订阅
+pub async fn mark_checked<C: ConnectionTrait>(db: &C, ids: Vec<Uuid>) -> Result<(), DbErr> {
+    Entity::update_many()
+        .col_expr(Column::LastCheckedAt, Expr::value(Utc::now()))
+        .filter(Column::Id.is_in(ids))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// 发送失败次数加一，返回累计次数
+pub async fn record_failed_delivery<C: ConnectionTrait>(
+    db: &C,
+    model: Model,
+) -> Result<i32, DbErr> {
+    let failed_deliveries = model.failed_deliveries + 1;
+    let mut active: ActiveModel = model.into();
+    active.failed_deliveries = Set(failed_deliveries);
+    active.update(db).await?;
+    Ok(failed_deliveries)
+}
+
+/// 标记订阅已提醒
+pub async fn mark_notified<C: ConnectionTrait>(db: &C, model: Model) -> Result<Model, DbErr> {
+    let mut active: ActiveModel = model.into();
+    active.notified_at = Set(Some(Utc::now()));
+    active.update(db).await
+}
+
+/// 按邮箱汇总的订阅用户
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct Subscriber {
+    pub email: String,
+    pub name: String,
+    /// 订阅数量
+    pub subscriptions: i64,
+    pub last_subscribed_at: DateTime<Utc>,
+}
+
+/// 按邮箱或称呼搜索订阅用户，页码从 1 开始，返回当前页和总数
+pub async fn search_subscribers<C: ConnectionTrait>(
+    db: &C,
+    keyword: Option<&str>,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<Subscriber>, u64), DbErr> {
+    let mut query = Entity::find()
+        .select_only()
+        .column(Column::Email)
+        .column_as(Column::Name.max(), "name")
+        .column_as(Column::Id.count(), "subscriptions")
+        .column_as(Column::SubscribedAt.max(), "last_subscribed_at")
+        .group_by(Column::Email)
+        .order_by_asc(Column::Email);
+    if let Some(keyword) = keyword {
+        query = query.filter(
+            Condition::any()
+                .add(Column::Email.contains(keyword))
+                .add(Column::Name.contains(keyword)),
+        );
+    }
+
+    let paginator = query.into_model::<Subscriber>().paginate(db, page_size);
+    let total = paginator.num_items().await?;
+    let items = paginator.fetch_page(page.saturating_sub(1)).await?;
+    Ok((items, total))
+}
+
+/// 按时间倒序查询某个邮箱的订阅，页码从 1 开始，返回当前页和总数
+pub async fn find_by_email<C: ConnectionTrait>(
+    db: &C,
+    email: &str,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<Model>, u64), DbErr> {
+    let paginator = Entity::find()
+        .filter(Column::Email.eq(email))
+        .order_by_desc(Column::SubscribedAt)
+        .paginate(db, page_size);
+    let total = paginator.num_items().await?;
+    let items = paginator.fetch_page(page.saturating_sub(1)).await?;
+    Ok((items, total))
+}
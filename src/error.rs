@@ -1,12 +1,36 @@
-use axum::response::{IntoResponse, Response};
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
 use reqwest::StatusCode;
+use serde::Serialize;
 use thiserror::Error;
 
+use crate::{middleware::current_request_id, telemetry::LogFilterError};
+
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("转链错误: {0}")]
     Translate(#[from] TranslateError),
 
+    #[error("数据库错误: {0}")]
+    Database(#[from] sea_orm::DbErr),
+
+    #[error("参数错误: {0}")]
+    InvalidParams(String),
+
+    #[error("资源不存在: {0}")]
+    NotFound(String),
+
+    #[error("未授权: {0}")]
+    Unauthorized(String),
+
+    #[error("禁止访问: {0}")]
+    Forbidden(String),
+
+    #[error("请求过于频繁: {0}")]
+    TooManyRequests(String),
+
     #[error("服务器内部错误: {0}")]
     Internal(String),
 
@@ -20,6 +44,9 @@ pub enum TranslateError {
     #[error("HTTP请求失败: {0}")]
     Request(String),
 
+    #[error("上游返回状态码: {0}")]
+    Status(u16),
+
     #[error("服务器内部错误: {0}")]
     Internal(String),
 
@@ -27,6 +54,19 @@ pub enum TranslateError {
     UnsupportedPlatform(String),
 }
 
+impl TranslateError {
+    /// 是否为上游不可用导致的错误，只有这类错误计入熔断
+    ///
+    /// 业务错误和响应解析失败说明上游仍能正常响应，不计入
+    pub fn is_upstream_failure(&self) -> bool {
+        match self {
+            TranslateError::Request(_) => true,
+            TranslateError::Status(status) => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
 /// 转链结果类型
 pub type TranslateResult<T> = Result<T, TranslateError>;
 
@@ -40,6 +80,26 @@ impl From<reqwest::Error> for TranslateError {
     }
 }
 
+/// 将日志过滤指令修改错误转换为 AppError
+impl From<LogFilterError> for AppError {
+    fn from(err: LogFilterError) -> Self {
+        match err {
+            LogFilterError::UnknownTarget(_) => AppError::NotFound(err.to_string()),
+            LogFilterError::InvalidDirective(_) => AppError::InvalidParams(err.to_string()),
+            LogFilterError::Reload(_) => AppError::Internal(err.to_string()),
+        }
+    }
+}
+
+/// 错误响应体
+#[derive(Serialize)]
+struct ErrorBody {
+    code: u16,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+}
+
 /// 实现 AppError 到 HTTP 响应的转换
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
@@ -47,9 +107,19 @@ impl IntoResponse for AppError {
             AppError::Translate(TranslateError::UnsupportedPlatform(_)) => {
                 (StatusCode::NOT_IMPLEMENTED, self.to_string())
             }
+            AppError::InvalidParams(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, self.to_string()),
+            AppError::Forbidden(_) => (StatusCode::FORBIDDEN, self.to_string()),
+            AppError::TooManyRequests(_) => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
-        (status, message).into_response()
+        let body = ErrorBody {
+            code: status.as_u16(),
+            message,
+            request_id: current_request_id(),
+        };
+        (status, Json(body)).into_response()
     }
 }
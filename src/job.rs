@@ -0,0 +1,69 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+mod order_sync;
+mod price_alert;
+mod price_refresher;
+mod rejection_flusher;
+
+pub use order_sync::sync_orders;
+pub use price_alert::spawn_price_alert;
+pub use price_refresher::spawn_price_refresher;
+pub use rejection_flusher::spawn_rejection_flusher;
+
+/// 心跳超过两个周期再加上该时长仍未更新时，认为任务已停止
+const HEARTBEAT_GRACE: Duration = Duration::from_secs(60);
+
+/// 记录后台任务心跳，用于存活检查
+#[derive(Clone, Default)]
+pub struct JobMonitor {
+    inner: Arc<Mutex<HashMap<&'static str, Heartbeat>>>,
+}
+
+struct Heartbeat {
+    last_beat: Instant,
+    period: Duration,
+}
+
+/// 后台任务存活状态
+#[derive(Debug, Serialize)]
+pub struct JobStatus {
+    pub name: &'static str,
+    pub alive: bool,
+    /// 距离上次心跳的秒数
+    pub last_beat_secs: u64,
+}
+
+impl JobMonitor {
+    /// 任务每轮开始前调用，`period` 为任务执行间隔
+    pub fn beat(&self, job: &'static str, period: Duration) {
+        self.inner.lock().unwrap().insert(
+            job,
+            Heartbeat {
+                last_beat: Instant::now(),
+                period,
+            },
+        );
+    }
+
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        self.inner
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, heartbeat)| {
+                let elapsed = heartbeat.last_beat.elapsed();
+                JobStatus {
+                    name,
+                    alive: elapsed <= heartbeat.period * 2 + HEARTBEAT_GRACE,
+                    last_beat_secs: elapsed.as_secs(),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::DatabaseConnection;
+use tracing::{info, warn};
+
+use crate::{
+    Platform, circuit_breaker::CircuitBreakers, configuration::ApplicationSettings, entity::orders,
+    error::TranslateError, metrics, route::translate::translator_from_settings,
+};
+
+/// 拼多多增量订单接口限制单次查询的时间跨度不超过 24 小时
+const WINDOW: Duration = Duration::hours(24);
+
+const PAGE_SIZE: u32 = 100;
+
+/// 同步 `since` 到 `until` 之间有更新的推广订单，返回写入的订单数
+///
+/// 按时间窗口和页码依次拉取所有已接入平台的订单，不支持查询订单的平台会被跳过。
+/// 每个窗口完成后更新 `order_sync_last_synced_timestamp_seconds`
+pub async fn sync_orders(
+    db: &DatabaseConnection,
+    settings: &ApplicationSettings,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> anyhow::Result<u64> {
+    let mut synced = 0;
+    // 命令行单独运行，熔断状态只在本次同步内有效
+    let breakers = CircuitBreakers::default();
+    for platform in Platform::SUPPORTED {
+        let translator = translator_from_settings(platform, settings, &breakers)?;
+        let mut start = since;
+        'windows: while start < until {
+            let end = (start + WINDOW).min(until);
+            let mut page = 1;
+            let mut fetched = 0;
+            loop {
+                let result = match translator
+                    .list_orders(start.timestamp(), end.timestamp(), page, PAGE_SIZE)
+                    .await
+                {
+                    Ok(result) => result,
+                    Err(TranslateError::UnsupportedPlatform(_)) => {
+                        warn!("{} 不支持查询订单，跳过", platform);
+                        break 'windows;
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                if result.orders.is_empty() {
+                    break;
+                }
+                fetched += result.orders.len() as i64;
+                for order in &result.orders {
+                    orders::upsert(db, platform, order).await?;
+                }
+                synced += result.orders.len() as u64;
+                if fetched >= result.total {
+                    break;
+                }
+                page += 1;
+            }
+            info!(
+                "{} {} - {} 的订单同步完成，共 {} 条",
+                platform, start, end, fetched
+            );
+            metrics::set_order_synced_until(platform.as_ref(), end);
+            start = end;
+        }
+    }
+    Ok(synced)
+}
@@ -0,0 +1,130 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use tokio::{task::JoinHandle, time::Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    Platform,
+    configuration::application::PriceAlertSettings,
+    entity::{alert_deliveries, subscriptions},
+    metrics,
+    money::Money,
+    notify::{Notifier, PriceDropNotification},
+    route::{AppState, translate::translator_for},
+};
+
+const JOB_NAME: &str = "price_alert";
+
+/// 启动降价提醒任务
+///
+/// 周期性地重新查询被订阅的商品，券后价低于目标价时发送提醒。每轮开始前读取最新
+/// 配置，修改检查间隔后从下一轮起生效
+pub fn spawn_price_alert(
+    state: AppState,
+    notifier: Arc<dyn Notifier>,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut period = check_period(&state.app_settings().price_alert);
+        let mut interval = tokio::time::interval(period);
+        loop {
+            state.job_monitor().beat(JOB_NAME, period);
+            // 只在两轮之间响应退出，保证每一轮完整执行
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+            let settings = state.app_settings().price_alert;
+            if check_period(&settings) != period {
+                period = check_period(&settings);
+                interval = tokio::time::interval_at(Instant::now() + period, period);
+            }
+            match check_subscriptions(&state, &settings, notifier.as_ref()).await {
+                Ok(()) => metrics::record_job_success(JOB_NAME),
+                Err(e) => warn!("检查降价提醒失败: {}", e),
+            }
+        }
+        info!("{} 已停止", JOB_NAME);
+    })
+}
+
+fn check_period(settings: &PriceAlertSettings) -> Duration {
+    Duration::from_secs(settings.check_interval_secs.max(1))
+}
+
+async fn check_subscriptions(
+    state: &AppState,
+    settings: &PriceAlertSettings,
+    notifier: &dyn Notifier,
+) -> anyhow::Result<()> {
+    let db = state.connection_pool();
+    let max_attempts = i32::try_from(settings.max_delivery_attempts).unwrap_or(i32::MAX);
+    let pending = subscriptions::find_pending(&db, max_attempts, settings.batch_size).await?;
+    // 先标记本轮已检查，查询失败的订阅也轮到队尾，不会挡住其余订阅
+    subscriptions::mark_checked(&db, pending.iter().map(|s| s.id).collect()).await?;
+
+    // 同一商品只查询一次
+    let mut by_goods: HashMap<(String, String), Vec<subscriptions::Model>> = HashMap::new();
+    for subscription in pending {
+        by_goods
+            .entry((subscription.platform.clone(), subscription.goods_id.clone()))
+            .or_default()
+            .push(subscription);
+    }
+
+    let mut notified = 0;
+    for ((platform, goods_id), subscriptions) in by_goods {
+        let Ok(platform) = platform.parse::<Platform>() else {
+            warn!("未知平台: {}", platform);
+            continue;
+        };
+        let translator = translator_for(platform, state)?;
+        let good_info = match translator.search_by_goods_id(&goods_id).await {
+            Ok(good_info) => good_info,
+            Err(e) => {
+                warn!("查询商品 {} 失败: {}", goods_id, e);
+                continue;
+            }
+        };
+        let price = good_info.coupon_discount_price;
+
+        for subscription in subscriptions {
+            let target_price = Money::from_cents(subscription.target_price);
+            if price >= target_price {
+                continue;
+            }
+
+            let notification = PriceDropNotification {
+                email: subscription.email.clone(),
+                name: subscription.name.clone(),
+                platform: platform.to_string(),
+                goods_id: goods_id.clone(),
+                goods_name: good_info.goods_name.clone(),
+                target_price,
+                price,
+            };
+            let error = notifier.notify(&notification).await.err().map(|e| {
+                warn!("发送降价提醒失败: {}", e);
+                e.to_string()
+            });
+            let delivered = error.is_none();
+            alert_deliveries::record(&db, subscription.id, notifier.channel(), price, error)
+                .await?;
+            // 发送失败的订阅保持待提醒状态，下一轮重试，失败次数达到上限后放弃
+            if delivered {
+                subscriptions::mark_notified(&db, subscription).await?;
+                notified += 1;
+            } else {
+                let id = subscription.id;
+                let failed = subscriptions::record_failed_delivery(&db, subscription).await?;
+                if failed >= max_attempts {
+                    warn!("订阅 {} 已连续发送失败 {} 次，不再重试", id, failed);
+                }
+            }
+        }
+    }
+    info!("降价提醒检查完成，共发送 {} 条", notified);
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::{task::JoinHandle, time::Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    Platform,
+    configuration::application::PriceHistorySettings,
+    entity::price_history,
+    metrics,
+    route::{AppState, translate::translator_for},
+};
+
+const JOB_NAME: &str = "price_refresher";
+
+/// 启动价格定时刷新任务
+///
+/// 周期性地重新查询最近被转链过的商品，补充价格历史。每轮开始前读取最新配置，
+/// 修改刷新间隔后从下一轮起生效
+pub fn spawn_price_refresher(state: AppState, shutdown: CancellationToken) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut period = refresh_period(&state.app_settings().price_history);
+        // 第一次 tick 立即返回，跳过以免启动时就刷新
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+        loop {
+            state.job_monitor().beat(JOB_NAME, period);
+            // 只在两轮之间响应退出，保证每一轮完整执行
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+            let settings = state.app_settings().price_history;
+            if refresh_period(&settings) != period {
+                period = refresh_period(&settings);
+                interval = tokio::time::interval_at(Instant::now() + period, period);
+            }
+            match refresh_prices(&state, &settings).await {
+                Ok(()) => metrics::record_job_success(JOB_NAME),
+                Err(e) => warn!("刷新商品价格失败: {}", e),
+            }
+        }
+        info!("{} 已停止", JOB_NAME);
+    })
+}
+
+fn refresh_period(settings: &PriceHistorySettings) -> Duration {
+    Duration::from_secs(settings.refresh_interval_secs.max(1))
+}
+
+async fn refresh_prices(state: &AppState, settings: &PriceHistorySettings) -> anyhow::Result<()> {
+    let db = state.connection_pool();
+    let since = chrono::Duration::try_days(settings.track_days)
+        .and_then(|days| Utc::now().checked_sub_signed(days))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC);
+    let tracked = price_history::find_tracked_since(&db, since, settings.batch_size).await?;
+
+    let mut refreshed = 0;
+    for goods in tracked {
+        let Ok(platform) = goods.platform.parse::<Platform>() else {
+            warn!("未知平台: {}", goods.platform);
+            continue;
+        };
+        let translator = translator_for(platform, state)?;
+        match translator.search_by_goods_id(&goods.goods_id).await {
+            Ok(good_info) => {
+                price_history::record(&db, platform, &goods.source_url, None, &good_info).await?;
+                refreshed += 1;
+            }
+            Err(e) => warn!("刷新商品 {} 价格失败: {}", goods.goods_id, e),
+        }
+    }
+    info!("商品价格刷新完成，共 {} 个", refreshed);
+
+    Ok(())
+}
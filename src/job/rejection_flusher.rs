@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use tokio::{task::JoinHandle, time::Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{entity::api_key_usage, metrics, route::AppState};
+
+const JOB_NAME: &str = "rejection_flusher";
+
+/// 启动定期写入 API 密钥拒绝次数的任务
+///
+/// 中间件只在内存中累计被拒绝的请求数，这里按 `rejection_flush_interval_secs`
+/// 批量写入数据库，退出前再写入一次
+pub fn spawn_rejection_flusher(state: AppState, shutdown: CancellationToken) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut period = flush_period(&state);
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+        loop {
+            state.job_monitor().beat(JOB_NAME, period);
+            let stopping = tokio::select! {
+                _ = shutdown.cancelled() => true,
+                _ = interval.tick() => false,
+            };
+            match flush_rejections(&state).await {
+                Ok(()) => metrics::record_job_success(JOB_NAME),
+                Err(e) => warn!("写入 API 密钥拒绝次数失败: {}", e),
+            }
+            if stopping {
+                break;
+            }
+            if flush_period(&state) != period {
+                period = flush_period(&state);
+                interval = tokio::time::interval_at(Instant::now() + period, period);
+            }
+        }
+        info!("{} 已停止", JOB_NAME);
+    })
+}
+
+fn flush_period(state: &AppState) -> Duration {
+    Duration::from_secs(
+        state
+            .app_settings()
+            .api_keys
+            .rejection_flush_interval_secs
+            .max(1),
+    )
+}
+
+/// 写入累计的拒绝次数，写入失败的放回内存等待下一轮
+async fn flush_rejections(state: &AppState) -> anyhow::Result<()> {
+    let rejections = state.rejections();
+    let db = state.connection_pool();
+    let mut result = Ok(());
+    for ((key, day), count) in rejections.take() {
+        if result.is_ok() {
+            result = api_key_usage::record_rejected(&db, key, day, count).await;
+        }
+        if result.is_err() {
+            rejections.restore(key, day, count);
+        }
+    }
+    Ok(result?)
+}
@@ -1,17 +1,35 @@
+pub mod api_key;
+pub mod cache;
+pub mod circuit_breaker;
+pub mod cli;
 pub mod configuration;
 pub mod entity;
 pub mod error;
+pub mod job;
+pub mod metrics;
 pub mod middleware;
+pub mod migration;
+pub mod money;
+pub mod notify;
+pub mod reload;
 pub mod route;
 pub mod startup;
 pub mod telemetry;
 pub mod util;
 
+use strum::{AsRefStr, Display, EnumString};
+
 /// 支持的平台枚举
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, AsRefStr, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
 pub enum Platform {
     /// 拼多多
     Pdd,
     /// 未知平台
     Unknown,
 }
+
+impl Platform {
+    /// 已接入的平台
+    pub const SUPPORTED: [Platform; 1] = [Platform::Pdd];
+}
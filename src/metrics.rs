@@ -0,0 +1,201 @@
+use std::{collections::BTreeMap, sync::LazyLock, time::Duration};
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+    core::Collector,
+};
+use serde::Serialize;
+
+/// 指标注册表，`/metrics` 导出其中的全部指标
+pub static REGISTRY: LazyLock<Registry> =
+    LazyLock::new(|| Registry::new_custom(Some("kuai_saver".to_string()), None).unwrap());
+
+static HTTP_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("http_requests_total", "HTTP 请求数"),
+        &["method", "route", "status"],
+    ))
+});
+
+static HTTP_REQUEST_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(HistogramVec::new(
+        HistogramOpts::new("http_request_duration_seconds", "HTTP 请求耗时"),
+        &["method", "route", "status"],
+    ))
+});
+
+static UPSTREAM_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("upstream_requests_total", "上游平台接口调用数"),
+        &["platform", "api", "outcome"],
+    ))
+});
+
+static UPSTREAM_REQUEST_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(HistogramVec::new(
+        HistogramOpts::new("upstream_request_duration_seconds", "上游平台接口调用耗时"),
+        &["platform", "api"],
+    ))
+});
+
+static CACHE_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("cache_requests_total", "缓存查询次数"),
+        &["cache", "result"],
+    ))
+});
+
+static CACHE_ENTRIES: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new("cache_entries", "缓存条目数"),
+        &["cache"],
+    ))
+});
+
+static DB_POOL_CONNECTIONS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new("db_pool_connections", "数据库连接池连接数"),
+        &["state"],
+    ))
+});
+
+static JOB_LAST_SUCCESS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new(
+            "job_last_success_timestamp_seconds",
+            "后台任务最近一次成功完成的时间",
+        ),
+        &["job"],
+    ))
+});
+
+static ORDER_SYNC_LAST_SYNCED: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new(
+            "order_sync_last_synced_timestamp_seconds",
+            "推广订单已同步到的时间，当前时间减去该值即为同步延迟",
+        ),
+        &["platform"],
+    ))
+});
+
+fn register<T: prometheus::core::Collector + Clone + 'static>(
+    collector: prometheus::Result<T>,
+) -> T {
+    let collector = collector.expect("指标定义错误");
+    REGISTRY
+        .register(Box::new(collector.clone()))
+        .expect("指标重复注册");
+    collector
+}
+
+/// 记录一次 HTTP 请求
+pub fn record_http_request(method: &str, route: &str, status: u16, elapsed: Duration) {
+    let status = status.to_string();
+    let labels = [method, route, status.as_str()];
+    HTTP_REQUESTS.with_label_values(&labels).inc();
+    HTTP_REQUEST_DURATION
+        .with_label_values(&labels)
+        .observe(elapsed.as_secs_f64());
+}
+
+/// 记录一次上游接口调用
+pub fn record_upstream_request(platform: &str, api: &str, success: bool, elapsed: Duration) {
+    let outcome = if success { "ok" } else { "error" };
+    UPSTREAM_REQUESTS
+        .with_label_values(&[platform, api, outcome])
+        .inc();
+    UPSTREAM_REQUEST_DURATION
+        .with_label_values(&[platform, api])
+        .observe(elapsed.as_secs_f64());
+}
+
+/// 进程启动以来某个上游接口的调用统计
+#[derive(Debug, Clone, Serialize)]
+pub struct UpstreamStats {
+    pub platform: String,
+    pub api: String,
+    pub total: u64,
+    pub errors: u64,
+    /// 失败占比，取值 0.0-1.0
+    pub error_rate: f64,
+}
+
+/// 按平台和接口汇总上游调用次数和失败率
+pub fn upstream_stats() -> Vec<UpstreamStats> {
+    let mut counts: BTreeMap<(String, String), (u64, u64)> = BTreeMap::new();
+    for family in UPSTREAM_REQUESTS.collect() {
+        for metric in family.get_metric() {
+            let label = |name: &str| {
+                metric
+                    .get_label()
+                    .iter()
+                    .find(|l| l.name() == name)
+                    .map_or(String::new(), |l| l.value().to_string())
+            };
+            let value = metric.get_counter().get_value() as u64;
+            let entry = counts.entry((label("platform"), label("api"))).or_default();
+            entry.0 += value;
+            if label("outcome") == "error" {
+                entry.1 += value;
+            }
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|((platform, api), (total, errors))| UpstreamStats {
+            platform,
+            api,
+            total,
+            errors,
+            error_rate: if total == 0 {
+                0.0
+            } else {
+                errors as f64 / total as f64
+            },
+        })
+        .collect()
+}
+
+/// 记录一次缓存查询
+pub fn record_cache_request(cache: &str, hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    CACHE_REQUESTS.with_label_values(&[cache, result]).inc();
+}
+
+/// 更新缓存条目数
+pub fn set_cache_entries(cache: &str, size: usize) {
+    CACHE_ENTRIES.with_label_values(&[cache]).set(size as i64);
+}
+
+/// 更新数据库连接池使用情况
+pub fn set_db_pool(size: u32, idle: usize) {
+    DB_POOL_CONNECTIONS
+        .with_label_values(&["idle"])
+        .set(idle as i64);
+    DB_POOL_CONNECTIONS
+        .with_label_values(&["active"])
+        .set(size as i64 - idle as i64);
+}
+
+/// 记录后台任务完成一轮
+pub fn record_job_success(job: &str) {
+    JOB_LAST_SUCCESS
+        .with_label_values(&[job])
+        .set(chrono::Utc::now().timestamp());
+}
+
+/// 记录平台的订单已同步到 `until`
+pub fn set_order_synced_until(platform: &str, until: chrono::DateTime<chrono::Utc>) {
+    ORDER_SYNC_LAST_SYNCED
+        .with_label_values(&[platform])
+        .set(until.timestamp());
+}
+
+/// 以 Prometheus 文本格式导出全部指标
+pub fn encode() -> anyhow::Result<String> {
+    let mut buffer = vec![];
+    TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
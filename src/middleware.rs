@@ -1 +1,213 @@
+use std::time::Instant;
 
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+use secrecy::{ExposeSecret, SecretString};
+use tracing::{Instrument, Span, field::Empty, info, info_span};
+use uuid::Uuid;
+
+use crate::{
+    api_key::{self, ApiScope},
+    configuration::application::AdminRole,
+    entity::api_key_usage,
+    error::{AppError, AppResult},
+    metrics,
+    route::AppState,
+};
+
+/// 请求 id 的请求头和响应头
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// 外部传入的请求 id 最大长度，超出时重新生成
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// 当前请求的 id，不在请求上下文中时返回 `None`
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// 接收或生成请求 id，并为每个请求创建 tracing span
+///
+/// span 中的 `platform` 和 `user_id` 由具体的 handler 在得知后补充
+pub async fn request_id(req: Request, next: Next) -> Response {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| is_valid_request_id(v))
+        .map_or_else(|| Uuid::new_v4().to_string(), str::to_string);
+
+    let span = info_span!(
+        "request",
+        request_id = %request_id,
+        method = %req.method(),
+        path = %req.uri().path(),
+        platform = Empty,
+        user_id = Empty,
+    );
+
+    let start = Instant::now();
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), next.run(req).instrument(span.clone()))
+        .await;
+    span.in_scope(|| {
+        info!(
+            status = response.status().as_u16(),
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "请求完成"
+        )
+    });
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// 通过令牌认证的管理接口调用者，由 [`require_admin`] 放入请求扩展
+#[derive(Debug, Clone)]
+pub struct AdminIdentity {
+    pub name: String,
+    pub role: AdminRole,
+}
+
+/// 校验管理接口的访问令牌，并记录调用者身份
+pub async fn require_admin(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> AppResult<Response> {
+    let Some(admin) = state.admin_settings() else {
+        return Err(AppError::Forbidden("管理接口未启用".to_string()));
+    };
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| AppError::Unauthorized("缺少访问令牌".to_string()))?;
+
+    let matches = |expected: &SecretString| {
+        constant_time_eq(token.as_bytes(), expected.expose_secret().as_bytes())
+    };
+    let identity = if matches(&admin.token) {
+        AdminIdentity {
+            name: "admin".to_string(),
+            role: AdminRole::Admin,
+        }
+    } else if let Some(operator) = admin.operators.iter().find(|o| matches(&o.token)) {
+        AdminIdentity {
+            name: operator.name.clone(),
+            role: operator.role,
+        }
+    } else {
+        return Err(AppError::Unauthorized("访问令牌无效".to_string()));
+    };
+
+    req.extensions_mut().insert(identity);
+    Ok(next.run(req).await)
+}
+
+/// 要求调用者至少拥有指定角色，需要在 [`require_admin`] 之后执行
+pub async fn require_role(
+    State(role): State<AdminRole>,
+    req: Request,
+    next: Next,
+) -> AppResult<Response> {
+    let identity = req
+        .extensions()
+        .get::<AdminIdentity>()
+        .ok_or_else(|| AppError::Unauthorized("缺少访问令牌".to_string()))?;
+    if identity.role < role {
+        return Err(AppError::Forbidden(format!(
+            "{} 没有执行该操作的权限",
+            identity.name
+        )));
+    }
+    Ok(next.run(req).await)
+}
+
+/// 校验 API 密钥的授权范围、频率限制和每日配额
+///
+/// 没有携带密钥的请求在 `api_keys.required` 关闭时直接放行；校验通过后把调用方
+/// 放入请求扩展，供 handler 使用
+pub async fn require_api_key(
+    State((state, scope)): State<(AppState, ApiScope)>,
+    req: Request,
+    next: Next,
+) -> AppResult<Response> {
+    let (mut parts, body) = req.into_parts();
+    let Some(key) = api_key::key_from_parts(&parts)? else {
+        if state.app_settings().api_keys.required {
+            return Err(AppError::Unauthorized("缺少 API 密钥".to_string()));
+        }
+        return Ok(next.run(Request::from_parts(parts, body)).await);
+    };
+
+    let identity = api_key::authenticate(&state, key).await?;
+    Span::current().record("user_id", identity.owner.as_str());
+    if !identity.allows(scope) {
+        return Err(AppError::Forbidden(format!(
+            "API 密钥 {} 没有 {} 权限",
+            identity.name, scope
+        )));
+    }
+
+    let db = state.connection_pool();
+    let today = Utc::now().date_naive();
+    let rejection = if !state
+        .rate_limiter()
+        .try_acquire(identity.id, identity.rate_limit_per_minute)
+    {
+        Some(format!(
+            "超出每分钟 {} 次的调用限制",
+            identity.rate_limit_per_minute
+        ))
+    } else if !api_key_usage::try_consume(&db, identity.id, today, identity.daily_quota).await? {
+        Some(format!("今日 {} 次调用配额已用完", identity.daily_quota))
+    } else {
+        None
+    };
+    if let Some(message) = rejection {
+        state.rejections().record(identity.id, today);
+        return Err(AppError::TooManyRequests(message));
+    }
+
+    parts.extensions.insert(identity);
+    Ok(next.run(Request::from_parts(parts, body)).await)
+}
+
+/// 比较令牌时不因提前返回泄露匹配长度
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 统计每个路由的请求数和耗时
+pub async fn track_metrics(req: Request, next: Next) -> Response {
+    // 使用路由模板而不是原始路径，避免标签基数过高
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", |p| p.as_str())
+        .to_string();
+    let method = req.method().to_string();
+
+    let start = Instant::now();
+    let response = next.run(req).await;
+    metrics::record_http_request(&method, &route, response.status().as_u16(), start.elapsed());
+
+    response
+}
@@ -0,0 +1,234 @@
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbErr, Statement, TransactionTrait,
+};
+use tracing::info;
+
+/// 一次表结构变更
+pub struct Migration {
+    /// 版本号，按升序执行
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// 所有表结构变更，与 `entity` 中各表的建表语句保持一致，只能追加不能修改
+///
+/// 版本 1-4 的表在引入迁移前按 `entity` 中的建表语句手工创建过，
+/// 使用 `IF NOT EXISTS` 让已有数据库也能从头执行
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_price_history",
+        sql: "CREATE TABLE IF NOT EXISTS price_history (
+            id BIGSERIAL NOT NULL,
+            PRIMARY KEY (id),
+            platform TEXT NOT NULL,
+            goods_id TEXT NOT NULL,
+            source_url TEXT NOT NULL,
+            origin_price BIGINT NOT NULL,
+            coupon_discount BIGINT NOT NULL,
+            price BIGINT NOT NULL,
+            promotion_rate BIGINT NOT NULL,
+            observed_at TIMESTAMPTZ NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS price_history_goods_idx ON price_history (platform, goods_id, observed_at);",
+    },
+    Migration {
+        version: 2,
+        name: "create_subscriptions",
+        sql: "CREATE TABLE IF NOT EXISTS subscriptions (
+            id uuid NOT NULL,
+            PRIMARY KEY (id),
+            email TEXT NOT NULL,
+            name TEXT NOT NULL,
+            platform TEXT NOT NULL,
+            goods_id TEXT NOT NULL,
+            target_price BIGINT NOT NULL,
+            subscribed_at TIMESTAMPTZ NOT NULL,
+            notified_at TIMESTAMPTZ
+        );
+        CREATE INDEX IF NOT EXISTS subscriptions_pending_idx ON subscriptions (notified_at);",
+    },
+    Migration {
+        version: 3,
+        name: "create_alert_deliveries",
+        sql: "CREATE TABLE IF NOT EXISTS alert_deliveries (
+            id BIGSERIAL NOT NULL,
+            PRIMARY KEY (id),
+            subscription_id uuid NOT NULL REFERENCES subscriptions (id) ON DELETE CASCADE,
+            channel TEXT NOT NULL,
+            price BIGINT NOT NULL,
+            success BOOLEAN NOT NULL,
+            error TEXT,
+            delivered_at TIMESTAMPTZ NOT NULL
+        );",
+    },
+    Migration {
+        version: 4,
+        name: "create_orders",
+        sql: "CREATE TABLE IF NOT EXISTS orders (
+            platform TEXT NOT NULL,
+            order_sn TEXT NOT NULL,
+            PRIMARY KEY (platform, order_sn),
+            goods_id TEXT NOT NULL,
+            goods_name TEXT NOT NULL,
+            pid TEXT NOT NULL,
+            order_amount BIGINT NOT NULL,
+            promotion_rate BIGINT NOT NULL,
+            promotion_amount BIGINT NOT NULL,
+            status INTEGER NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL,
+            modified_at TIMESTAMPTZ NOT NULL,
+            synced_at TIMESTAMPTZ NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS orders_modified_idx ON orders (modified_at);",
+    },
+    Migration {
+        version: 5,
+        name: "create_audit_log",
+        sql: "CREATE TABLE audit_log (
+            id BIGSERIAL NOT NULL,
+            PRIMARY KEY (id),
+            actor TEXT NOT NULL,
+            action TEXT NOT NULL,
+            target TEXT NOT NULL,
+            before JSONB,
+            after JSONB,
+            request_id TEXT,
+            created_at TIMESTAMPTZ NOT NULL
+        );
+        CREATE INDEX audit_log_actor_idx ON audit_log (actor, created_at);
+        CREATE INDEX audit_log_target_idx ON audit_log (target, created_at);
+        CREATE RULE audit_log_no_update AS ON UPDATE TO audit_log DO INSTEAD NOTHING;
+        CREATE RULE audit_log_no_delete AS ON DELETE TO audit_log DO INSTEAD NOTHING;",
+    },
+    Migration {
+        version: 6,
+        name: "create_api_keys",
+        sql: "CREATE TABLE api_keys (
+            id uuid NOT NULL,
+            PRIMARY KEY (id),
+            name TEXT NOT NULL,
+            owner TEXT NOT NULL,
+            pid TEXT,
+            key_prefix TEXT NOT NULL,
+            key_hash TEXT NOT NULL UNIQUE,
+            scopes TEXT NOT NULL,
+            rate_limit_per_minute INTEGER NOT NULL,
+            daily_quota BIGINT NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL,
+            revoked_at TIMESTAMPTZ
+        );
+        CREATE INDEX api_keys_owner_idx ON api_keys (owner);
+        CREATE TABLE api_key_usage (
+            api_key_id uuid NOT NULL REFERENCES api_keys (id) ON DELETE CASCADE,
+            day DATE NOT NULL,
+            PRIMARY KEY (api_key_id, day),
+            requests BIGINT NOT NULL,
+            rejected BIGINT NOT NULL
+        );",
+    },
+    Migration {
+        version: 7,
+        name: "add_price_history_pid",
+        sql: "ALTER TABLE price_history ADD COLUMN pid TEXT;
+        CREATE INDEX price_history_pid_idx ON price_history (pid, observed_at);",
+    },
+    Migration {
+        version: 8,
+        name: "reject_audit_log_changes",
+        sql: "DROP RULE IF EXISTS audit_log_no_update ON audit_log;
+        DROP RULE IF EXISTS audit_log_no_delete ON audit_log;
+        CREATE FUNCTION audit_log_append_only() RETURNS trigger LANGUAGE plpgsql AS $$
+        BEGIN
+            RAISE EXCEPTION 'audit_log 只允许追加，不能执行 %', TG_OP;
+        END;
+        $$;
+        CREATE TRIGGER audit_log_no_modify BEFORE UPDATE OR DELETE ON audit_log
+            FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
+        CREATE TRIGGER audit_log_no_truncate BEFORE TRUNCATE ON audit_log
+            FOR EACH STATEMENT EXECUTE FUNCTION audit_log_append_only();",
+    },
+    Migration {
+        version: 9,
+        name: "add_subscription_check_state",
+        sql: "ALTER TABLE subscriptions ADD COLUMN last_checked_at TIMESTAMPTZ;
+        ALTER TABLE subscriptions ADD COLUMN failed_deliveries INTEGER NOT NULL DEFAULT 0;
+        DROP INDEX IF EXISTS subscriptions_pending_idx;
+        CREATE INDEX subscriptions_pending_idx ON subscriptions (last_checked_at)
+            WHERE notified_at IS NULL;",
+    },
+    Migration {
+        version: 10,
+        name: "add_subscription_api_key",
+        sql: "ALTER TABLE subscriptions ADD COLUMN api_key_id uuid REFERENCES api_keys (id);
+        CREATE INDEX subscriptions_api_key_idx ON subscriptions (api_key_id);",
+    },
+];
+
+/// 执行表结构变更时持有的事务级咨询锁
+const MIGRATION_LOCK: i64 = 0x6b75_6169_5f6d_6967;
+
+/// 执行尚未执行的表结构变更，返回本次执行的变更
+///
+/// 已执行的版本记录在 `schema_migrations` 表中，每个变更在单独的事务中执行。
+/// 事务开始时获取咨询锁并重新检查版本，多个进程同时执行时每个变更只执行一次
+pub async fn run(db: &DatabaseConnection) -> Result<Vec<&'static Migration>, DbErr> {
+    let backend = db.get_database_backend();
+
+    let txn = db.begin().await?;
+    lock(&txn).await?;
+    txn.execute_unprepared(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version BIGINT NOT NULL,
+            PRIMARY KEY (version),
+            name TEXT NOT NULL,
+            applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )",
+    )
+    .await?;
+    txn.commit().await?;
+
+    let applied = applied_versions(db).await?;
+    let mut executed = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| !applied.contains(&m.version)) {
+        let txn = db.begin().await?;
+        lock(&txn).await?;
+        if applied_versions(&txn).await?.contains(&migration.version) {
+            continue;
+        }
+        txn.execute_unprepared(migration.sql).await?;
+        txn.execute(Statement::from_sql_and_values(
+            backend,
+            "INSERT INTO schema_migrations (version, name) VALUES ($1, $2)",
+            [migration.version.into(), migration.name.into()],
+        ))
+        .await?;
+        txn.commit().await?;
+        info!("已执行表结构变更 {} {}", migration.version, migration.name);
+        executed.push(migration);
+    }
+    Ok(executed)
+}
+
+/// 获取咨询锁，事务结束时自动释放
+async fn lock(txn: &DatabaseTransaction) -> Result<(), DbErr> {
+    txn.execute(Statement::from_sql_and_values(
+        txn.get_database_backend(),
+        "SELECT pg_advisory_xact_lock($1)",
+        [MIGRATION_LOCK.into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+async fn applied_versions(db: &impl ConnectionTrait) -> Result<Vec<i64>, DbErr> {
+    db.query_all(Statement::from_string(
+        db.get_database_backend(),
+        "SELECT version FROM schema_migrations",
+    ))
+    .await?
+    .iter()
+    .map(|row| row.try_get("", "version"))
+    .collect()
+}
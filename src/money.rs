@@ -0,0 +1,219 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+/// 金额，内部以分为单位存储，序列化为两位小数的元字符串（如 `"12.34"`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn from_cents(cents: i64) -> Self {
+        Money(cents)
+    }
+
+    pub const fn cents(self) -> i64 {
+        self.0
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    pub fn checked_add(self, rhs: Money) -> Option<Money> {
+        self.0.checked_add(rhs.0).map(Money)
+    }
+
+    pub fn checked_sub(self, rhs: Money) -> Option<Money> {
+        self.0.checked_sub(rhs.0).map(Money)
+    }
+
+    /// 按比例计算金额，不足一分的部分舍去
+    pub fn checked_mul_rate(self, rate: Rate) -> Option<Money> {
+        self.0
+            .checked_mul(rate.0)
+            .map(|v| v / Rate::PERMILLE_BASE)
+            .map(Money)
+    }
+
+    /// 计算券后价
+    ///
+    /// 未达到优惠券使用门槛时按原价计算，券后价最低为零
+    pub fn after_coupon(self, discount: Money, min_order_amount: Money) -> Money {
+        if !discount.is_positive() || self < min_order_amount {
+            return self;
+        }
+        self.checked_sub(discount)
+            .map_or(Money::ZERO, |v| v.max(Money::ZERO))
+    }
+}
+
+impl Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        write!(f, "{}{}.{:02}", sign, abs / 100, abs % 100)
+    }
+}
+
+impl FromStr for Money {
+    type Err = String;
+
+    /// 解析以元为单位的金额，最多两位小数
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("无效金额: {}", s);
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (yuan, fen) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |v: &str| v.bytes().all(|b| b.is_ascii_digit());
+        if yuan.is_empty() || fen.len() > 2 || !all_digits(yuan) || !all_digits(fen) {
+            return Err(invalid());
+        }
+        let yuan: i64 = yuan.parse().map_err(|_| invalid())?;
+        let fen: i64 = match fen.len() {
+            0 => 0,
+            1 => fen.parse::<i64>().map_err(|_| invalid())? * 10,
+            _ => fen.parse().map_err(|_| invalid())?,
+        };
+        let cents = yuan
+            .checked_mul(100)
+            .and_then(|v| v.checked_add(fen))
+            .ok_or_else(invalid)?;
+        Ok(Money(if negative { -cents } else { cents }))
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// 比例，内部以千分比存储，序列化为百分数（如 `12.5` 表示 12.5%）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rate(i64);
+
+impl Rate {
+    const PERMILLE_BASE: i64 = 1000;
+
+    pub const fn from_permille(permille: i64) -> Self {
+        Rate(permille)
+    }
+
+    pub const fn permille(self) -> i64 {
+        self.0
+    }
+
+    pub fn percent(self) -> f64 {
+        self.0 as f64 / 10.0
+    }
+}
+
+impl Display for Rate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}%", self.percent())
+    }
+}
+
+impl Serialize for Rate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.percent())
+    }
+}
+
+impl<'de> Deserialize<'de> for Rate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let percent = f64::deserialize(deserializer)?;
+        if !percent.is_finite() || !(0.0..=100.0).contains(&percent) {
+            return Err(de::Error::custom(format!(
+                "比例必须在 0-100 之间: {}",
+                percent
+            )));
+        }
+        Ok(Rate((percent * 10.0).round() as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yuan(s: &str) -> Money {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn after_coupon_ignores_coupon_below_threshold() {
+        let price = yuan("19.90");
+        assert_eq!(price.after_coupon(yuan("5"), yuan("20")), price);
+        assert_eq!(price.after_coupon(yuan("5"), yuan("19.90")), yuan("14.90"));
+        assert_eq!(price.after_coupon(Money::ZERO, Money::ZERO), price);
+    }
+
+    #[test]
+    fn after_coupon_never_goes_below_zero() {
+        assert_eq!(yuan("3").after_coupon(yuan("5"), Money::ZERO), Money::ZERO);
+    }
+
+    #[test]
+    fn parses_yuan_with_up_to_two_decimals() {
+        assert_eq!(yuan("12.3").cents(), 1230);
+        assert_eq!(yuan("12.34").cents(), 1234);
+        assert_eq!(yuan("-1").cents(), -100);
+        for invalid in [
+            "12.345",
+            "",
+            ".5",
+            "-",
+            "1.2.3",
+            "1e3",
+            "99999999999999999999",
+        ] {
+            assert!(
+                invalid.parse::<Money>().is_err(),
+                "{:?} 应该解析失败",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn displays_two_decimals() {
+        assert_eq!(Money::from_cents(-5).to_string(), "-0.05");
+        assert_eq!(Money::from_cents(1230).to_string(), "12.30");
+    }
+
+    #[test]
+    fn checked_mul_rate_truncates_fractional_cents() {
+        // 9.99 元 * 12.5% = 1.24875 元
+        let amount = yuan("9.99").checked_mul_rate(Rate::from_permille(125));
+        assert_eq!(amount, Some(yuan("1.24")));
+        assert_eq!(
+            Money::from_cents(i64::MAX).checked_mul_rate(Rate::from_permille(2)),
+            None
+        );
+    }
+
+    #[test]
+    fn rate_deserializes_from_percent() {
+        let rate: Rate = serde_json::from_str("12.5").unwrap();
+        assert_eq!(rate.permille(), 125);
+        for invalid in ["-0.1", "100.1", "1e400"] {
+            assert!(
+                serde_json::from_str::<Rate>(invalid).is_err(),
+                "{}",
+                invalid
+            );
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::{
+    configuration::application::NotifierSettings,
+    money::Money,
+    notify::{
+        local::{FileNotifier, LogNotifier},
+        smtp::SmtpNotifier,
+    },
+};
+
+mod local;
+mod smtp;
+
+/// 通知发送接口
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// 发送渠道名称，用于记录发送结果
+    fn channel(&self) -> &'static str;
+
+    /// 发送降价提醒
+    async fn notify(&self, notification: &PriceDropNotification) -> anyhow::Result<()>;
+}
+
+/// 降价提醒内容
+#[derive(Debug, Serialize)]
+pub struct PriceDropNotification {
+    pub email: String,
+    pub name: String,
+    pub platform: String,
+    pub goods_id: String,
+    pub goods_name: String,
+    /// 订阅的目标价
+    pub target_price: Money,
+    /// 当前券后价
+    pub price: Money,
+}
+
+impl PriceDropNotification {
+    pub fn subject(&self) -> String {
+        format!("降价提醒: {} 已降至 ¥{}", self.goods_name, self.price)
+    }
+
+    pub fn body(&self) -> String {
+        format!(
+            "{}，你好：\n\n你关注的商品「{}」当前券后价为 ¥{}，已低于目标价 ¥{}。\n",
+            self.name, self.goods_name, self.price, self.target_price
+        )
+    }
+}
+
+/// 根据配置创建通知发送器
+pub fn build_notifier(settings: &NotifierSettings) -> anyhow::Result<Arc<dyn Notifier>> {
+    let notifier: Arc<dyn Notifier> = match settings {
+        NotifierSettings::Log => Arc::new(LogNotifier),
+        NotifierSettings::File { path } => Arc::new(FileNotifier::new(path)),
+        NotifierSettings::Smtp(smtp) => Arc::new(SmtpNotifier::new(smtp)?),
+    };
+    Ok(notifier)
+}
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt};
+use tracing::info;
+
+use crate::notify::{Notifier, PriceDropNotification};
+
+/// 只打印日志的通知发送器，用于本地开发
+#[derive(Debug, Default)]
+pub struct LogNotifier;
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    fn channel(&self) -> &'static str {
+        "log"
+    }
+
+    async fn notify(&self, notification: &PriceDropNotification) -> anyhow::Result<()> {
+        info!(email = %notification.email, "{}", notification.subject());
+        Ok(())
+    }
+}
+
+/// 以 JSON Lines 格式追加写入文件的通知发送器，用于测试
+#[derive(Debug)]
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl Notifier for FileNotifier {
+    fn channel(&self) -> &'static str {
+        "file"
+    }
+
+    async fn notify(&self, notification: &PriceDropNotification) -> anyhow::Result<()> {
+        let mut line = serde_json::to_vec(notification)?;
+        line.push(b'\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(&line).await?;
+        Ok(())
+    }
+}
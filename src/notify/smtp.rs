@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor, message::header::ContentType,
+    transport::smtp::authentication::Credentials,
+};
+use secrecy::ExposeSecret;
+
+use crate::{
+    configuration::application::SmtpSettings,
+    notify::{Notifier, PriceDropNotification},
+};
+
+/// 通过 SMTP 发送邮件的通知发送器
+pub struct SmtpNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: String,
+}
+
+impl SmtpNotifier {
+    pub fn new(settings: &SmtpSettings) -> anyhow::Result<Self> {
+        let credentials = Credentials::new(
+            settings.username.clone(),
+            settings.password.expose_secret().to_string(),
+        );
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::relay(&settings.host)?
+            .port(settings.port)
+            .credentials(credentials)
+            .build();
+        Ok(Self {
+            transport,
+            from: settings.from.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    fn channel(&self) -> &'static str {
+        "smtp"
+    }
+
+    async fn notify(&self, notification: &PriceDropNotification) -> anyhow::Result<()> {
+        let message = Message::builder()
+            .from(self.from.parse()?)
+            .to(notification.email.parse()?)
+            .subject(notification.subject())
+            .header(ContentType::TEXT_PLAIN)
+            .body(notification.body())?;
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use std::time::Duration;
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::json;
+use tokio::{sync::mpsc, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::{
+    configuration::{LoadOptions, Settings, flatten_config},
+    entity::audit_log::{self, AuditEntry},
+    route::AppState,
+};
+
+/// 文件变化后等待的时间，编辑器保存时通常会连续触发多个事件
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 修改后需要重启才能生效的配置项
+const RESTART_REQUIRED: [&str; 9] = [
+    "application.host",
+    "application.port",
+    "application.tls",
+    "application.unix_socket",
+    "application.shutdown_timeout_secs",
+    "application.price_alert.notifier",
+    "db",
+    "log.log_dir",
+    "log.otlp",
+];
+
+/// 日志输出目标中可以热加载的字段，其余字段修改后需要重启
+const RELOADABLE_LOG_TARGET_FIELDS: [&str; 2] = ["level", "filter"];
+
+/// 两次加载之间变化的配置项路径
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ConfigChanges {
+    /// 已热加载生效的配置项
+    pub reloaded: Vec<String>,
+    /// 需要重启才能生效的配置项
+    pub restart_required: Vec<String>,
+}
+
+impl ConfigChanges {
+    /// 比较两份原始配置，按配置项路径分类
+    pub fn between(old: &config::Config, new: &config::Config) -> anyhow::Result<Self> {
+        let (old, new) = (flatten_config(old)?, flatten_config(new)?);
+        let mut changes = ConfigChanges::default();
+        let keys = old
+            .keys()
+            .chain(new.keys().filter(|k| !old.contains_key(*k)));
+        for key in keys {
+            if old.get(key) == new.get(key) {
+                continue;
+            }
+            if requires_restart(key) {
+                changes.restart_required.push(key.clone());
+            } else {
+                changes.reloaded.push(key.clone());
+            }
+        }
+        Ok(changes)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reloaded.is_empty() && self.restart_required.is_empty()
+    }
+}
+
+fn requires_restart(key: &str) -> bool {
+    let under = |prefix: &str| {
+        key.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+    };
+    if RESTART_REQUIRED.iter().any(|prefix| under(prefix)) {
+        return true;
+    }
+    // 增删输出目标或修改输出方式需要重建日志层
+    under("log.targets")
+        && !RELOADABLE_LOG_TARGET_FIELDS
+            .iter()
+            .any(|field| key.ends_with(&format!("].{}", field)))
+}
+
+/// 启动配置热加载任务
+///
+/// 配置目录或 `--config` 所在目录中的文件变化、收到 SIGHUP 时重新加载并校验配置，
+/// 校验通过后替换运行时配置（平台凭证、缓存、后台任务参数、管理接口令牌等）和日志
+/// 过滤指令；监听地址、数据库等配置修改后只记录告警，重启后生效
+pub fn spawn_config_reloader(
+    state: AppState,
+    options: LoadOptions,
+    source: config::Config,
+    shutdown: CancellationToken,
+) -> anyhow::Result<JoinHandle<()>> {
+    let config_dir = options.config_dir.clone();
+    let (watched_dir, watched_file) = (config_dir.clone(), options.config_file.clone());
+    let (tx, mut rx) = mpsc::channel(1);
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event
+            && matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            )
+            && event.paths.iter().any(|path| {
+                path.parent() == Some(watched_dir.as_path())
+                    || watched_file.as_deref() == Some(path.as_path())
+            })
+        {
+            // 通道已满说明已有待处理的重新加载
+            let _ = tx.try_send(());
+        }
+    })?;
+    watcher.watch(&config_dir, RecursiveMode::NonRecursive)?;
+    // 编辑器保存时常以替换文件的方式写入，监听所在目录才不会丢失后续变化
+    if let Some(dir) = options.config_file.as_deref().and_then(|f| f.parent())
+        && !dir.as_os_str().is_empty()
+        && dir != config_dir
+    {
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    }
+    let mut hangup = Hangup::new()?;
+    info!("监听配置目录 {} 的变化", config_dir.display());
+
+    Ok(tokio::spawn(async move {
+        let _watcher: RecommendedWatcher = watcher;
+        let mut current = source;
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = hangup.recv() => info!("收到 SIGHUP，重新加载配置"),
+                Some(()) = rx.recv() => {
+                    tokio::time::sleep(DEBOUNCE).await;
+                    while rx.try_recv().is_ok() {}
+                    debug!("配置文件发生变化，重新加载配置");
+                }
+            }
+            match reload(&state, &options, &current) {
+                Ok((next, changes)) => {
+                    current = next;
+                    state.set_reload_error(None);
+                    if !changes.is_empty() {
+                        audit_changes(&state, changes).await;
+                    }
+                }
+                Err(e) => {
+                    warn!("重新加载配置失败，继续使用当前配置: {:#}", e);
+                    state.set_reload_error(Some(format!("{:#}", e)));
+                }
+            }
+        }
+        info!("配置热加载已停止");
+    }))
+}
+
+/// 重新加载配置并应用可以热加载的部分，返回新的原始配置和变化的配置项
+fn reload(
+    state: &AppState,
+    options: &LoadOptions,
+    current: &config::Config,
+) -> anyhow::Result<(config::Config, ConfigChanges)> {
+    let source = Settings::source(options)?;
+    let settings: Settings = source.clone().try_deserialize()?;
+    settings.validate()?;
+
+    let changes = ConfigChanges::between(current, &source)?;
+    if changes.is_empty() {
+        debug!("配置没有变化");
+        return Ok((source, changes));
+    }
+
+    // 需要重启的配置保持运行中的值，避免与实际状态不一致
+    let running = state.app_settings();
+    let mut application = settings.application;
+    application.host = running.host;
+    application.port = running.port;
+    application.tls = running.tls;
+    application.unix_socket = running.unix_socket;
+    application.shutdown_timeout_secs = running.shutdown_timeout_secs;
+    application.price_alert.notifier = running.price_alert.notifier;
+    state.update_app_settings(application);
+
+    let log_filters = state.log_filters();
+    for (i, target) in settings.log.targets.iter().enumerate() {
+        let prefix = format!("log.targets[{}].", i);
+        if !changes.reloaded.iter().any(|key| key.starts_with(&prefix)) {
+            continue;
+        }
+        let name = target.name();
+        if let Err(e) = log_filters.set_default(&name, &target.directive()) {
+            warn!("更新日志输出目标 {} 的过滤指令失败: {}", name, e);
+        }
+    }
+
+    if !changes.reloaded.is_empty() {
+        info!(keys = ?changes.reloaded, "配置已重新加载");
+    }
+    if !changes.restart_required.is_empty() {
+        warn!(keys = ?changes.restart_required, "以下配置修改需要重启才能生效");
+    }
+    Ok((source, changes))
+}
+
+/// 只记录变化的配置项路径，不记录取值，避免密钥写入审计记录
+async fn audit_changes(state: &AppState, changes: ConfigChanges) {
+    let entry = AuditEntry {
+        actor: "system".to_string(),
+        action: "config.reload",
+        target: "configuration".to_string(),
+        before: None,
+        after: Some(json!({
+            "reloaded": changes.reloaded,
+            "restart_required": changes.restart_required,
+        })),
+    };
+    audit_log::record_or_warn(&state.connection_pool(), entry).await;
+}
+
+/// SIGHUP 信号，非 unix 平台上永远不会触发
+struct Hangup {
+    #[cfg(unix)]
+    signal: tokio::signal::unix::Signal,
+}
+
+impl Hangup {
+    #[cfg(unix)]
+    fn new() -> std::io::Result<Self> {
+        use tokio::signal::unix::{SignalKind, signal};
+        Ok(Self {
+            signal: signal(SignalKind::hangup())?,
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn new() -> std::io::Result<Self> {
+        Ok(Self {})
+    }
+
+    #[cfg(unix)]
+    async fn recv(&mut self) {
+        self.signal.recv().await;
+    }
+
+    #[cfg(not(unix))]
+    async fn recv(&mut self) {
+        std::future::pending::<()>().await
+    }
+}
@@ -1,12 +1,45 @@
 use std::sync::{Arc, Mutex};
 
-use axum::{Router, routing::get};
+use axum::{
+    Router,
+    middleware::{from_fn, from_fn_with_state},
+    routing::{delete, get, post, put},
+};
 use sea_orm::DatabaseConnection;
 
-use crate::{configuration::ApplicationSettings, route::translate::translate_link};
+use crate::{
+    api_key::{ApiScope, RateLimiter, RejectionCounter},
+    cache::TtlCache,
+    circuit_breaker::CircuitBreakers,
+    configuration::application::AdminRole,
+    configuration::{ApplicationSettings, application::AdminSettings},
+    job::JobMonitor,
+    middleware::{request_id, require_admin, require_api_key, require_role, track_metrics},
+    route::{
+        admin::{
+            issue_api_key, list_api_keys, list_audit_log, list_links, list_log_filters,
+            list_orders, reset_log_filter, resync_order, revoke_api_key, search_users,
+            set_log_filter, upstream_stats, user_subscriptions,
+        },
+        api_keys::key_usage,
+        feed::{recommend_goods, top_goods},
+        goods::{goods_history, search_goods},
+        health::{live, ready},
+        metrics::export_metrics,
+        subscription::{create_subscription, delete_subscription},
+        translate::{GoodInfo, GoodsPage, translate_link},
+    },
+    telemetry::LogFilters,
+};
 
-mod order_detail;
-mod translate;
+mod admin;
+mod api_keys;
+mod feed;
+mod goods;
+mod health;
+mod metrics;
+mod subscription;
+pub(crate) mod translate;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -15,27 +48,158 @@ pub struct AppState {
 
 #[derive(Clone)]
 struct AppStateInner {
-    #[allow(dead_code)]
     connection_pool: DatabaseConnection,
     app_settings: ApplicationSettings,
+    goods_cache: TtlCache<GoodInfo>,
+    feed_cache: TtlCache<GoodsPage>,
+    log_filters: LogFilters,
+    job_monitor: JobMonitor,
+    rate_limiter: RateLimiter,
+    rejections: RejectionCounter,
+    circuit_breakers: CircuitBreakers,
+    /// 最近一次重新加载配置失败的原因，成功后清除
+    reload_error: Option<String>,
 }
 
 impl AppState {
-    pub fn new(pool: DatabaseConnection, app_settings: ApplicationSettings) -> Self {
+    pub fn new(
+        pool: DatabaseConnection,
+        app_settings: ApplicationSettings,
+        log_filters: LogFilters,
+    ) -> Self {
         let inner = AppStateInner {
             connection_pool: pool,
+            log_filters,
+            job_monitor: JobMonitor::default(),
+            rate_limiter: RateLimiter::default(),
+            rejections: RejectionCounter::default(),
+            circuit_breakers: CircuitBreakers::default(),
+            reload_error: None,
+            goods_cache: TtlCache::new("goods", &app_settings.cache),
+            feed_cache: TtlCache::new("feed", &app_settings.cache),
             app_settings,
         };
         Self {
             inner: Arc::new(Mutex::new(inner)),
         }
     }
+
+    pub fn connection_pool(&self) -> DatabaseConnection {
+        self.inner.lock().unwrap().connection_pool.clone()
+    }
+
+    pub fn goods_cache(&self) -> TtlCache<GoodInfo> {
+        self.inner.lock().unwrap().goods_cache.clone()
+    }
+
+    pub fn feed_cache(&self) -> TtlCache<GoodsPage> {
+        self.inner.lock().unwrap().feed_cache.clone()
+    }
+
+    pub fn log_filters(&self) -> LogFilters {
+        self.inner.lock().unwrap().log_filters.clone()
+    }
+
+    pub fn job_monitor(&self) -> JobMonitor {
+        self.inner.lock().unwrap().job_monitor.clone()
+    }
+
+    pub fn rate_limiter(&self) -> RateLimiter {
+        self.inner.lock().unwrap().rate_limiter.clone()
+    }
+
+    pub fn rejections(&self) -> RejectionCounter {
+        self.inner.lock().unwrap().rejections.clone()
+    }
+
+    pub fn circuit_breakers(&self) -> CircuitBreakers {
+        self.inner.lock().unwrap().circuit_breakers.clone()
+    }
+
+    pub fn reload_error(&self) -> Option<String> {
+        self.inner.lock().unwrap().reload_error.clone()
+    }
+
+    pub fn set_reload_error(&self, error: Option<String>) {
+        self.inner.lock().unwrap().reload_error = error;
+    }
+
+    pub fn app_settings(&self) -> ApplicationSettings {
+        self.inner.lock().unwrap().app_settings.clone()
+    }
+
+    /// 替换运行时配置，缓存参数变化时重建缓存
+    pub fn update_app_settings(&self, app_settings: ApplicationSettings) {
+        let mut inner = self.inner.lock().unwrap();
+        if app_settings.cache != inner.app_settings.cache {
+            inner.goods_cache = TtlCache::new("goods", &app_settings.cache);
+            inner.feed_cache = TtlCache::new("feed", &app_settings.cache);
+        }
+        inner.app_settings = app_settings;
+    }
+
+    pub fn admin_settings(&self) -> Option<AdminSettings> {
+        self.inner.lock().unwrap().app_settings.admin.clone()
+    }
 }
 
 pub fn get_router(state: AppState) -> Router {
-    Router::new()
-        .route("/ping", get(|| async { "pong" }))
+    // 只读接口所有角色都可以访问，其余接口按角色限制
+    let operator = Router::new()
+        .route("/orders/{platform}/{order_sn}/sync", post(resync_order))
+        .route_layer(from_fn_with_state(AdminRole::Operator, require_role));
+    let admin_only = Router::new()
+        .route(
+            "/log/filters/{target}",
+            put(set_log_filter).delete(reset_log_filter),
+        )
+        .route("/audit", get(list_audit_log))
+        .route("/api_keys", get(list_api_keys).post(issue_api_key))
+        .route("/api_keys/{id}", delete(revoke_api_key))
+        .route_layer(from_fn_with_state(AdminRole::Admin, require_role));
+    let admin = Router::new()
+        .route("/log/filters", get(list_log_filters))
+        .route("/users", get(search_users))
+        .route("/users/{email}/subscriptions", get(user_subscriptions))
+        .route("/links", get(list_links))
+        .route("/orders", get(list_orders))
+        .route("/upstream", get(upstream_stats))
+        .merge(operator)
+        .merge(admin_only)
+        .route_layer(from_fn_with_state(state.clone(), require_admin));
+    // 会生成推广链接的接口，合作方密钥绑定的推广位在这里生效
+    let partner = Router::new()
         .route("/translate_link", get(translate_link))
+        // 旧版客户端使用的转链地址
         .route("/order_detail", get(translate_link))
+        .route("/goods/search", get(search_goods))
+        .route("/goods/recommend", get(recommend_goods))
+        .route("/goods/top", get(top_goods))
+        .route_layer(from_fn_with_state(
+            (state.clone(), ApiScope::Translate),
+            require_api_key,
+        ));
+
+    // 订阅会向任意邮箱发送邮件，即使未要求转链密钥也必须携带密钥
+    let subscriber = Router::new()
+        .route("/subscriptions", post(create_subscription))
+        .route("/subscriptions/{id}", delete(delete_subscription))
+        .route_layer(from_fn_with_state(
+            (state.clone(), ApiScope::Subscribe),
+            require_api_key,
+        ));
+
+    Router::new()
+        .route("/ping", get(|| async { "pong" }))
+        .route("/health/live", get(live))
+        .route("/health/ready", get(ready))
+        .route("/metrics", get(export_metrics))
+        .route("/api_keys/usage", get(key_usage))
+        .route("/goods/{platform}/{id}/history", get(goods_history))
+        .merge(partner)
+        .merge(subscriber)
+        .nest("/admin", admin)
+        .layer(from_fn(track_metrics))
+        .layer(from_fn(request_id))
         .with_state(state)
 }
@@ -0,0 +1,163 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::info;
+
+use crate::{
+    entity::audit_log::{self, AuditEntry},
+    error::{AppError, AppResult},
+    middleware::AdminIdentity,
+    route::AppState,
+    telemetry::LogFilterInfo,
+};
+
+mod api_keys;
+mod audit;
+mod links;
+mod orders;
+mod upstream;
+mod users;
+
+pub use api_keys::{issue_api_key, list_api_keys, revoke_api_key};
+pub use audit::list_audit_log;
+pub use links::list_links;
+pub use orders::{list_orders, resync_order};
+pub use upstream::upstream_stats;
+pub use users::{search_users, user_subscriptions};
+
+/// 单页最多返回的记录数
+const MAX_PAGE_SIZE: u64 = 100;
+
+/// 分页参数，页码从 1 开始
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct PageParams {
+    #[serde(default = "default_page")]
+    page: u64,
+    #[serde(default = "default_page_size")]
+    page_size: u64,
+}
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_page_size() -> u64 {
+    20
+}
+
+impl PageParams {
+    fn check(&self) -> AppResult<()> {
+        if self.page == 0 {
+            return Err(AppError::InvalidParams("page 从 1 开始".to_string()));
+        }
+        if self.page_size == 0 || self.page_size > MAX_PAGE_SIZE {
+            return Err(AppError::InvalidParams(format!(
+                "page_size 取值范围为 1-{}",
+                MAX_PAGE_SIZE
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 分页结果
+#[derive(Debug, Serialize)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub page: u64,
+    pub page_size: u64,
+    pub total: u64,
+}
+
+impl<T> Paginated<T> {
+    fn new(items: Vec<T>, total: u64, params: PageParams) -> Self {
+        Self {
+            items,
+            page: params.page,
+            page_size: params.page_size,
+            total,
+        }
+    }
+
+    /// 对内存中的全部记录分页
+    fn from_all(all: Vec<T>, params: PageParams) -> Self {
+        let total = all.len() as u64;
+        let skip = (params.page - 1).saturating_mul(params.page_size);
+        let items = all
+            .into_iter()
+            .skip(skip as usize)
+            .take(params.page_size as usize)
+            .collect();
+        Self::new(items, total, params)
+    }
+}
+
+/// 修改日志过滤指令参数
+#[derive(Debug, Deserialize)]
+pub struct SetLogFilterParams {
+    /// EnvFilter 指令，如 `info,kuai_saver::route::translate=trace`
+    directive: String,
+    /// 多少秒后自动恢复为默认指令，不传时不恢复
+    revert_after_secs: Option<u64>,
+}
+
+pub async fn list_log_filters(State(state): State<AppState>) -> Json<Vec<LogFilterInfo>> {
+    Json(state.log_filters().list())
+}
+
+pub async fn set_log_filter(
+    Path(target): Path<String>,
+    State(state): State<AppState>,
+    Extension(identity): Extension<AdminIdentity>,
+    Json(params): Json<SetLogFilterParams>,
+) -> AppResult<Json<LogFilterInfo>> {
+    let before = current_log_filter(&state, &target);
+    let revert_after = params.revert_after_secs.map(std::time::Duration::from_secs);
+    let filter = state
+        .log_filters()
+        .set(&target, &params.directive, revert_after)?;
+    info!(operator = %identity.name, target = %target, directive = %params.directive, "日志过滤指令已修改");
+    audit_log_filter(&state, &identity, "log_filter.set", before, &filter).await;
+    Ok(Json(filter))
+}
+
+pub async fn reset_log_filter(
+    Path(target): Path<String>,
+    State(state): State<AppState>,
+    Extension(identity): Extension<AdminIdentity>,
+) -> AppResult<Json<LogFilterInfo>> {
+    let before = current_log_filter(&state, &target);
+    let filter = state.log_filters().reset(&target)?;
+    info!(operator = %identity.name, target = %target, "日志过滤指令已恢复默认");
+    audit_log_filter(&state, &identity, "log_filter.reset", before, &filter).await;
+    Ok(Json(filter))
+}
+
+fn current_log_filter(state: &AppState, target: &str) -> Option<LogFilterInfo> {
+    state
+        .log_filters()
+        .list()
+        .into_iter()
+        .find(|filter| filter.target == target)
+}
+
+/// 日志过滤指令只存在于内存中，修改生效后再写审计记录
+async fn audit_log_filter(
+    state: &AppState,
+    identity: &AdminIdentity,
+    action: &'static str,
+    before: Option<LogFilterInfo>,
+    after: &LogFilterInfo,
+) {
+    let entry = AuditEntry {
+        actor: identity.name.clone(),
+        action,
+        target: format!("log_filter:{}", after.target),
+        before: before.map(|filter| json!(filter)),
+        after: Some(json!(after)),
+    };
+    audit_log::record_or_warn(&state.connection_pool(), entry).await;
+}
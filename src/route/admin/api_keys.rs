@@ -0,0 +1,210 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use sea_orm::TransactionTrait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    api_key::{self, ApiScope},
+    entity::{
+        api_keys::{self, NewApiKey},
+        audit_log::{self, AuditEntry},
+    },
+    error::{AppError, AppResult},
+    middleware::AdminIdentity,
+    route::{
+        AppState,
+        admin::{PageParams, Paginated},
+    },
+};
+
+/// 签发密钥参数
+#[derive(Debug, Deserialize)]
+pub struct IssueApiKeyParams {
+    name: String,
+    /// 所属合作方
+    owner: String,
+    /// 合作方推广位，不传时使用默认推广位
+    pid: Option<String>,
+    scopes: Vec<ApiScope>,
+    /// 不传时使用 `api_keys.default_rate_limit_per_minute`
+    rate_limit_per_minute: Option<u32>,
+    /// 不传时使用 `api_keys.default_daily_quota`
+    daily_quota: Option<u64>,
+}
+
+/// 密钥查询参数
+#[derive(Debug, Deserialize)]
+pub struct ApiKeyFilter {
+    owner: Option<String>,
+}
+
+/// 密钥信息，不包含密钥本身
+#[derive(Debug, Serialize)]
+pub struct ApiKeyInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub owner: String,
+    pub pid: Option<String>,
+    /// 密钥开头几位
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: i32,
+    pub daily_quota: i64,
+    /// 签发时间，秒级时间戳
+    pub created_at: i64,
+    /// 吊销时间，秒级时间戳
+    pub revoked_at: Option<i64>,
+}
+
+impl From<api_keys::Model> for ApiKeyInfo {
+    fn from(model: api_keys::Model) -> Self {
+        ApiKeyInfo {
+            id: model.id,
+            name: model.name,
+            owner: model.owner,
+            pid: model.pid,
+            key_prefix: model.key_prefix,
+            scopes: model
+                .scopes
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+            rate_limit_per_minute: model.rate_limit_per_minute,
+            daily_quota: model.daily_quota,
+            created_at: model.created_at.timestamp(),
+            revoked_at: model.revoked_at.map(|t| t.timestamp()),
+        }
+    }
+}
+
+/// 新签发的密钥，`key` 只返回这一次
+#[derive(Debug, Serialize)]
+pub struct IssuedApiKey {
+    pub key: String,
+    #[serde(flatten)]
+    pub info: ApiKeyInfo,
+}
+
+pub async fn issue_api_key(
+    State(state): State<AppState>,
+    Extension(identity): Extension<AdminIdentity>,
+    Json(params): Json<IssueApiKeyParams>,
+) -> AppResult<(StatusCode, Json<IssuedApiKey>)> {
+    if params.name.trim().is_empty() || params.owner.trim().is_empty() {
+        return Err(AppError::InvalidParams(
+            "name 和 owner 不能为空".to_string(),
+        ));
+    }
+    if params.scopes.is_empty() {
+        return Err(AppError::InvalidParams("scopes 不能为空".to_string()));
+    }
+    let defaults = state.app_settings().api_keys;
+    let rate_limit = params
+        .rate_limit_per_minute
+        .unwrap_or(defaults.default_rate_limit_per_minute);
+    let daily_quota = params.daily_quota.unwrap_or(defaults.default_daily_quota);
+    let (Ok(rate_limit), Ok(daily_quota)) = (i32::try_from(rate_limit), i64::try_from(daily_quota))
+    else {
+        return Err(AppError::InvalidParams("限额超出范围".to_string()));
+    };
+    if rate_limit == 0 || daily_quota == 0 {
+        return Err(AppError::InvalidParams("限额必须大于 0".to_string()));
+    }
+
+    let generated = api_key::generate_key();
+    let mut scopes: Vec<String> = params.scopes.iter().map(|s| s.to_string()).collect();
+    scopes.sort_unstable();
+    scopes.dedup();
+    let new_key = NewApiKey {
+        name: params.name,
+        owner: params.owner,
+        pid: params.pid.filter(|pid| !pid.trim().is_empty()),
+        key_prefix: generated.prefix,
+        key_hash: generated.hash,
+        scopes,
+        rate_limit_per_minute: rate_limit,
+        daily_quota,
+    };
+
+    let txn = state.connection_pool().begin().await?;
+    let info = ApiKeyInfo::from(api_keys::create(&txn, new_key).await?);
+    audit_log::record(
+        &txn,
+        AuditEntry {
+            actor: identity.name.clone(),
+            action: "api_key.issue",
+            target: format!("api_key:{}", info.id),
+            before: None,
+            after: Some(json!(info)),
+        },
+    )
+    .await?;
+    txn.commit().await?;
+
+    info!(operator = %identity.name, key_id = %info.id, owner = %info.owner, "API 密钥已签发");
+    Ok((
+        StatusCode::CREATED,
+        Json(IssuedApiKey {
+            key: generated.key,
+            info,
+        }),
+    ))
+}
+
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    Query(filter): Query<ApiKeyFilter>,
+    Query(page): Query<PageParams>,
+) -> AppResult<Json<Paginated<ApiKeyInfo>>> {
+    page.check()?;
+    let (items, total) = api_keys::find_page(
+        &state.connection_pool(),
+        filter.owner.as_deref(),
+        page.page,
+        page.page_size,
+    )
+    .await?;
+    Ok(Json(Paginated::new(
+        items.into_iter().map(ApiKeyInfo::from).collect(),
+        total,
+        page,
+    )))
+}
+
+/// 吊销密钥，重复吊销不会报错
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    Extension(identity): Extension<AdminIdentity>,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<ApiKeyInfo>> {
+    let txn = state.connection_pool().begin().await?;
+    let model = api_keys::find(&txn, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("API 密钥 {}", id)))?;
+    if model.revoked_at.is_some() {
+        return Ok(Json(model.into()));
+    }
+    let before = ApiKeyInfo::from(model.clone());
+    let after = ApiKeyInfo::from(api_keys::revoke(&txn, model).await?);
+    audit_log::record(
+        &txn,
+        AuditEntry {
+            actor: identity.name.clone(),
+            action: "api_key.revoke",
+            target: format!("api_key:{}", id),
+            before: Some(json!(before)),
+            after: Some(json!(after)),
+        },
+    )
+    .await?;
+    txn.commit().await?;
+
+    info!(operator = %identity.name, key_id = %id, "API 密钥已吊销");
+    Ok(Json(after))
+}
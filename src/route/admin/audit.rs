@@ -0,0 +1,84 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    entity::audit_log::{self, AuditFilter},
+    error::{AppError, AppResult},
+    route::{
+        AppState,
+        admin::{PageParams, Paginated},
+    },
+};
+
+/// 审计记录查询参数
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    actor: Option<String>,
+    target: Option<String>,
+    /// 起始时间（含），秒级时间戳
+    since: Option<i64>,
+    /// 截止时间（不含），秒级时间戳
+    until: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditRecord {
+    pub id: i64,
+    pub actor: String,
+    pub action: String,
+    pub target: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub request_id: Option<String>,
+    /// 操作时间，秒级时间戳
+    pub created_at: i64,
+}
+
+impl From<audit_log::Model> for AuditRecord {
+    fn from(model: audit_log::Model) -> Self {
+        AuditRecord {
+            id: model.id,
+            actor: model.actor,
+            action: model.action,
+            target: model.target,
+            before: model.before,
+            after: model.after,
+            request_id: model.request_id,
+            created_at: model.created_at.timestamp(),
+        }
+    }
+}
+
+pub async fn list_audit_log(
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+    Query(page): Query<PageParams>,
+) -> AppResult<Json<Paginated<AuditRecord>>> {
+    page.check()?;
+    let timestamp = |name: &str, secs: Option<i64>| {
+        secs.map(|secs| {
+            DateTime::from_timestamp(secs, 0)
+                .ok_or_else(|| AppError::InvalidParams(format!("{} 不是有效的时间戳", name)))
+        })
+        .transpose()
+    };
+    let filter = AuditFilter {
+        actor: query.actor,
+        target: query.target,
+        since: timestamp("since", query.since)?,
+        until: timestamp("until", query.until)?,
+    };
+
+    let (items, total) =
+        audit_log::find_page(&state.connection_pool(), &filter, page.page, page.page_size).await?;
+    Ok(Json(Paginated::new(
+        items.into_iter().map(AuditRecord::from).collect(),
+        total,
+        page,
+    )))
+}
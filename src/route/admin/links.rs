@@ -0,0 +1,68 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::price_history,
+    error::AppResult,
+    money::{Money, Rate},
+    route::{
+        AppState,
+        admin::{PageParams, Paginated},
+    },
+};
+
+/// 链接查询参数
+#[derive(Debug, Deserialize)]
+pub struct LinkFilter {
+    /// 合作方密钥绑定的推广位 id
+    pid: String,
+}
+
+/// 合作方转过的链接及当时的价格
+#[derive(Debug, Serialize)]
+pub struct LinkSummary {
+    pub platform: String,
+    pub goods_id: String,
+    pub source_url: String,
+    pub price: Money,
+    pub promotion_rate: Rate,
+    /// 转链时间，秒级时间戳
+    pub observed_at: i64,
+}
+
+impl From<price_history::Model> for LinkSummary {
+    fn from(model: price_history::Model) -> Self {
+        LinkSummary {
+            platform: model.platform,
+            goods_id: model.goods_id,
+            source_url: model.source_url,
+            price: Money::from_cents(model.price),
+            promotion_rate: Rate::from_permille(model.promotion_rate),
+            observed_at: model.observed_at.timestamp(),
+        }
+    }
+}
+
+/// 推广位转过的链接，命中缓存的重复转链不会记录
+pub async fn list_links(
+    State(state): State<AppState>,
+    Query(filter): Query<LinkFilter>,
+    Query(page): Query<PageParams>,
+) -> AppResult<Json<Paginated<LinkSummary>>> {
+    page.check()?;
+    let (items, total) = price_history::find_by_pid(
+        &state.connection_pool(),
+        &filter.pid,
+        page.page,
+        page.page_size,
+    )
+    .await?;
+    Ok(Json(Paginated::new(
+        items.into_iter().map(LinkSummary::from).collect(),
+        total,
+        page,
+    )))
+}
@@ -0,0 +1,125 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use sea_orm::TransactionTrait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::info;
+
+use crate::{
+    entity::{
+        audit_log::{self, AuditEntry},
+        orders,
+    },
+    error::AppResult,
+    middleware::AdminIdentity,
+    money::{Money, Rate},
+    route::{
+        AppState,
+        admin::{PageParams, Paginated},
+        goods::parse_platform,
+        translate::translator_for,
+    },
+};
+
+/// 订单查询参数
+#[derive(Debug, Deserialize)]
+pub struct OrderFilter {
+    /// 推广位 id
+    pid: Option<String>,
+    status: Option<i32>,
+}
+
+/// 已同步的推广订单
+#[derive(Debug, Serialize)]
+pub struct OrderSummary {
+    pub platform: String,
+    pub order_sn: String,
+    pub goods_id: String,
+    pub goods_name: String,
+    pub pid: String,
+    pub order_amount: Money,
+    pub promotion_rate: Rate,
+    pub promotion_amount: Money,
+    pub status: i32,
+    /// 下单时间，秒级时间戳
+    pub created_at: i64,
+    /// 平台侧最后更新时间，秒级时间戳
+    pub modified_at: i64,
+    /// 最近一次同步时间，秒级时间戳
+    pub synced_at: i64,
+}
+
+impl From<orders::Model> for OrderSummary {
+    fn from(model: orders::Model) -> Self {
+        OrderSummary {
+            platform: model.platform,
+            order_sn: model.order_sn,
+            goods_id: model.goods_id,
+            goods_name: model.goods_name,
+            pid: model.pid,
+            order_amount: Money::from_cents(model.order_amount),
+            promotion_rate: Rate::from_permille(model.promotion_rate),
+            promotion_amount: Money::from_cents(model.promotion_amount),
+            status: model.status,
+            created_at: model.created_at.timestamp(),
+            modified_at: model.modified_at.timestamp(),
+            synced_at: model.synced_at.timestamp(),
+        }
+    }
+}
+
+pub async fn list_orders(
+    State(state): State<AppState>,
+    Query(filter): Query<OrderFilter>,
+    Query(page): Query<PageParams>,
+) -> AppResult<Json<Paginated<OrderSummary>>> {
+    page.check()?;
+    let (items, total) = orders::find_page(
+        &state.connection_pool(),
+        filter.pid.as_deref(),
+        filter.status,
+        page.page,
+        page.page_size,
+    )
+    .await?;
+    Ok(Json(Paginated::new(
+        items.into_iter().map(OrderSummary::from).collect(),
+        total,
+        page,
+    )))
+}
+
+/// 从平台重新拉取单个订单并更新本地记录，更新和审计记录在同一事务中写入
+pub async fn resync_order(
+    State(state): State<AppState>,
+    Extension(identity): Extension<AdminIdentity>,
+    Path((platform, order_sn)): Path<(String, String)>,
+) -> AppResult<Json<OrderSummary>> {
+    let platform = parse_platform(&platform)?;
+    let order = translator_for(platform, &state)?
+        .order_detail(&order_sn)
+        .await?;
+
+    let txn = state.connection_pool().begin().await?;
+    let before = orders::find(&txn, platform, &order_sn)
+        .await?
+        .map(OrderSummary::from);
+    let after = OrderSummary::from(orders::upsert(&txn, platform, &order).await?);
+    audit_log::record(
+        &txn,
+        AuditEntry {
+            actor: identity.name.clone(),
+            action: "order.resync",
+            target: format!("order:{}:{}", platform, order_sn),
+            before: before.map(|order| json!(order)),
+            after: Some(json!(after)),
+        },
+    )
+    .await?;
+    txn.commit().await?;
+
+    info!(operator = %identity.name, %platform, order_sn = %order_sn, "订单已重新同步");
+    Ok(Json(after))
+}
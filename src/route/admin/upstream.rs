@@ -0,0 +1,47 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use serde::Serialize;
+
+use crate::{
+    Platform,
+    circuit_breaker::BreakerState,
+    error::AppResult,
+    metrics::{self, UpstreamStats},
+    route::{
+        AppState,
+        admin::{PageParams, Paginated},
+    },
+};
+
+/// 上游接口的调用统计和所属平台的熔断状态
+#[derive(Debug, Serialize)]
+pub struct UpstreamStatus {
+    #[serde(flatten)]
+    pub stats: UpstreamStats,
+    pub breaker: Option<BreakerState>,
+}
+
+/// 进程启动以来各上游接口的调用次数和失败率，失败率高的排在前面
+pub async fn upstream_stats(
+    State(state): State<AppState>,
+    Query(page): Query<PageParams>,
+) -> AppResult<Json<Paginated<UpstreamStatus>>> {
+    page.check()?;
+    let mut stats = metrics::upstream_stats();
+    stats.sort_by(|a, b| b.error_rate.total_cmp(&a.error_rate));
+    let breakers = state.circuit_breakers();
+    let statuses = stats
+        .into_iter()
+        .map(|stats| UpstreamStatus {
+            breaker: stats
+                .platform
+                .parse::<Platform>()
+                .ok()
+                .map(|platform| breakers.for_platform(platform).state()),
+            stats,
+        })
+        .collect();
+    Ok(Json(Paginated::from_all(statuses, page)))
+}
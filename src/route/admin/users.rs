@@ -0,0 +1,81 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::subscriptions,
+    error::AppResult,
+    route::{
+        AppState,
+        admin::{PageParams, Paginated},
+        subscription::SubscriptionInfo,
+    },
+};
+
+/// 用户搜索参数
+#[derive(Debug, Deserialize)]
+pub struct UserSearchParams {
+    /// 按邮箱或称呼模糊匹配，不传时列出全部用户
+    q: Option<String>,
+}
+
+/// 用户概况，目前用户以订阅时填写的邮箱区分
+#[derive(Debug, Serialize)]
+pub struct UserSummary {
+    pub email: String,
+    pub name: String,
+    pub subscriptions: i64,
+    /// 最近一次订阅时间，秒级时间戳
+    pub last_subscribed_at: i64,
+}
+
+impl From<subscriptions::Subscriber> for UserSummary {
+    fn from(subscriber: subscriptions::Subscriber) -> Self {
+        UserSummary {
+            email: subscriber.email,
+            name: subscriber.name,
+            subscriptions: subscriber.subscriptions,
+            last_subscribed_at: subscriber.last_subscribed_at.timestamp(),
+        }
+    }
+}
+
+pub async fn search_users(
+    State(state): State<AppState>,
+    Query(search): Query<UserSearchParams>,
+    Query(page): Query<PageParams>,
+) -> AppResult<Json<Paginated<UserSummary>>> {
+    page.check()?;
+    let keyword = search.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let (items, total) = subscriptions::search_subscribers(
+        &state.connection_pool(),
+        keyword,
+        page.page,
+        page.page_size,
+    )
+    .await?;
+    Ok(Json(Paginated::new(
+        items.into_iter().map(UserSummary::from).collect(),
+        total,
+        page,
+    )))
+}
+
+/// 用户订阅过的商品
+pub async fn user_subscriptions(
+    State(state): State<AppState>,
+    Path(email): Path<String>,
+    Query(page): Query<PageParams>,
+) -> AppResult<Json<Paginated<SubscriptionInfo>>> {
+    page.check()?;
+    let (items, total) =
+        subscriptions::find_by_email(&state.connection_pool(), &email, page.page, page.page_size)
+            .await?;
+    Ok(Json(Paginated::new(
+        items.into_iter().map(SubscriptionInfo::from).collect(),
+        total,
+        page,
+    )))
+}
@@ -0,0 +1,87 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api_key::{ApiKeyIdentity, ApiScope},
+    entity::api_key_usage,
+    error::{AppError, AppResult},
+    route::AppState,
+};
+
+/// 最多查询多少天的用量
+const MAX_USAGE_DAYS: i64 = 90;
+
+/// 用量查询参数
+#[derive(Debug, Deserialize)]
+pub struct UsageParams {
+    #[serde(default = "default_days")]
+    days: i64,
+}
+
+fn default_days() -> i64 {
+    30
+}
+
+/// 调用方自己的密钥信息和每日用量
+#[derive(Debug, Serialize)]
+pub struct KeyUsage {
+    pub name: String,
+    pub owner: String,
+    pub scopes: Vec<ApiScope>,
+    pub rate_limit_per_minute: u32,
+    pub daily_quota: i64,
+    /// 今日剩余配额
+    pub remaining_today: i64,
+    /// 按日期倒序排列
+    pub usage: Vec<DailyUsage>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DailyUsage {
+    /// UTC 日期，如 `2025-06-01`
+    pub day: String,
+    pub requests: i64,
+    pub rejected: i64,
+}
+
+/// 查询调用方密钥的用量，用 `X-Api-Key` 认证
+pub async fn key_usage(
+    State(state): State<AppState>,
+    identity: ApiKeyIdentity,
+    Query(params): Query<UsageParams>,
+) -> AppResult<Json<KeyUsage>> {
+    if !(1..=MAX_USAGE_DAYS).contains(&params.days) {
+        return Err(AppError::InvalidParams(format!(
+            "days 取值范围为 1-{}",
+            MAX_USAGE_DAYS
+        )));
+    }
+    let today = Utc::now().date_naive();
+    let since = today - Duration::days(params.days - 1);
+    let usage = api_key_usage::find_since(&state.connection_pool(), identity.id, since).await?;
+
+    let used_today = usage
+        .iter()
+        .find(|u| u.day == today)
+        .map_or(0, |u| u.requests);
+    Ok(Json(KeyUsage {
+        name: identity.name,
+        owner: identity.owner,
+        scopes: identity.scopes,
+        rate_limit_per_minute: identity.rate_limit_per_minute,
+        daily_quota: identity.daily_quota,
+        remaining_today: (identity.daily_quota - used_today).max(0),
+        usage: usage
+            .into_iter()
+            .map(|u| DailyUsage {
+                day: u.day.to_string(),
+                requests: u.requests,
+                rejected: u.rejected,
+            })
+            .collect(),
+    }))
+}
@@ -0,0 +1,133 @@
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+};
+use serde::Deserialize;
+
+use crate::{
+    api_key::ApiKeyIdentity,
+    error::{AppError, AppResult},
+    route::{
+        AppState,
+        goods::{default_platform, parse_platform},
+        translate::{FeedQuery, GoodsPage, translator_for},
+    },
+};
+
+/// 单页最多返回的商品数量
+const MAX_FEED_LIMIT: u32 = 100;
+
+/// 推荐商品查询参数
+#[derive(Debug, Deserialize)]
+pub struct RecommendParams {
+    #[serde(default = "default_platform")]
+    platform: String,
+    /// 拼多多：0-1.9包邮；1-今日爆款；2-品牌清仓；4-猜你喜欢；5-实时热销；6-实时收益；7-今日畅销；8-高佣榜单
+    #[serde(default = "default_channel_type")]
+    channel_type: i32,
+    list_id: Option<String>,
+    #[serde(default)]
+    offset: u32,
+    #[serde(default = "default_limit")]
+    limit: u32,
+}
+
+/// 爆品榜单查询参数
+#[derive(Debug, Deserialize)]
+pub struct TopGoodsParams {
+    #[serde(default = "default_platform")]
+    platform: String,
+    /// 拼多多：1-实时热销榜；2-实时收益榜
+    #[serde(default = "default_sort_type")]
+    sort_type: i32,
+    list_id: Option<String>,
+    #[serde(default)]
+    offset: u32,
+    #[serde(default = "default_limit")]
+    limit: u32,
+}
+
+fn default_channel_type() -> i32 {
+    1
+}
+
+fn default_sort_type() -> i32 {
+    1
+}
+
+fn default_limit() -> u32 {
+    20
+}
+
+fn feed_query(
+    list_id: Option<String>,
+    offset: u32,
+    limit: u32,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+) -> AppResult<FeedQuery> {
+    if limit == 0 || limit > MAX_FEED_LIMIT {
+        return Err(AppError::InvalidParams(format!(
+            "limit 取值范围为 1-{}",
+            MAX_FEED_LIMIT
+        )));
+    }
+    Ok(FeedQuery {
+        list_id,
+        offset,
+        limit,
+        // 推广位只能来自合作方密钥，不能由调用方指定
+        pid: api_key.and_then(|Extension(key)| key.pid),
+    })
+}
+
+pub async fn recommend_goods(
+    Query(params): Query<RecommendParams>,
+    State(state): State<AppState>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+) -> AppResult<Json<GoodsPage>> {
+    let platform = parse_platform(&params.platform)?;
+    let query = feed_query(params.list_id, params.offset, params.limit, api_key)?;
+
+    let cache = state.feed_cache();
+    let key = format!(
+        "{}:recommend:{}:{}",
+        platform,
+        params.channel_type,
+        query.cache_key()
+    );
+    if let Some(page) = cache.get(&key) {
+        return Ok(Json(page));
+    }
+
+    let translator = translator_for(platform, &state)?;
+    let page = translator.recommend(params.channel_type, &query).await?;
+
+    cache.insert(key, page.clone());
+    Ok(Json(page))
+}
+
+pub async fn top_goods(
+    Query(params): Query<TopGoodsParams>,
+    State(state): State<AppState>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+) -> AppResult<Json<GoodsPage>> {
+    let platform = parse_platform(&params.platform)?;
+    let query = feed_query(params.list_id, params.offset, params.limit, api_key)?;
+
+    let cache = state.feed_cache();
+    let key = format!(
+        "{}:top:{}:{}",
+        platform,
+        params.sort_type,
+        query.cache_key()
+    );
+    if let Some(page) = cache.get(&key) {
+        return Ok(Json(page));
+    }
+
+    let translator = translator_for(platform, &state)?;
+    let page = translator.top_goods(params.sort_type, &query).await?;
+
+    cache.insert(key, page.clone());
+    Ok(Json(page))
+}
@@ -0,0 +1,203 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use serde::{Deserialize, Serialize};
+use tracing::Span;
+
+use crate::{
+    Platform,
+    api_key::ApiKeyIdentity,
+    entity::price_history,
+    error::{AppError, AppResult, TranslateError},
+    money::{Money, Rate},
+    route::{
+        AppState,
+        translate::{GoodsPage, SearchQuery, SearchSort, translator_for},
+    },
+};
+
+/// 单页最多返回的商品数量
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// 单次最多返回的价格记录数量
+const MAX_HISTORY_LIMIT: u64 = 1000;
+
+/// 关键词搜索参数
+#[derive(Debug, Deserialize)]
+pub struct SearchParams {
+    q: String,
+    #[serde(default = "default_platform")]
+    platform: String,
+    #[serde(default)]
+    sort: SearchSort,
+    #[serde(default = "default_page")]
+    page: u32,
+    #[serde(default = "default_page_size")]
+    page_size: u32,
+    #[serde(default)]
+    with_coupon: bool,
+    /// 券后价下限，单位为元
+    min_price: Option<Money>,
+    /// 券后价上限，单位为元
+    max_price: Option<Money>,
+    list_id: Option<String>,
+}
+
+/// 未指定平台时默认使用拼多多
+pub(crate) fn default_platform() -> String {
+    "pdd".to_string()
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_page_size() -> u32 {
+    20
+}
+
+/// 价格历史查询参数
+#[derive(Debug, Deserialize)]
+pub struct HistoryParams {
+    #[serde(default = "default_history_limit")]
+    limit: u64,
+}
+
+fn default_history_limit() -> u64 {
+    100
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoodsHistory {
+    pub platform: String,
+    pub goods_id: String,
+    /// 观测期内的最低券后价
+    pub lowest_price: Option<Money>,
+    /// 按时间倒序排列的价格记录
+    pub observations: Vec<PriceObservation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PriceObservation {
+    /// 券后价
+    pub price: Money,
+    pub origin_price: Money,
+    pub coupon_discount: Money,
+    pub promotion_rate: Rate,
+    /// 观测时间，秒级时间戳
+    pub observed_at: i64,
+}
+
+impl From<price_history::Model> for PriceObservation {
+    fn from(model: price_history::Model) -> Self {
+        PriceObservation {
+            price: Money::from_cents(model.price),
+            origin_price: Money::from_cents(model.origin_price),
+            coupon_discount: Money::from_cents(model.coupon_discount),
+            promotion_rate: Rate::from_permille(model.promotion_rate),
+            observed_at: model.observed_at.timestamp(),
+        }
+    }
+}
+
+/// 解析路径或参数中的平台名称
+///
+/// 解析成功后记录到当前请求的 span 中
+pub(crate) fn parse_platform(platform: &str) -> AppResult<Platform> {
+    let platform: Platform = platform
+        .parse()
+        .ok()
+        .filter(|p| *p != Platform::Unknown)
+        .ok_or_else(|| {
+            AppError::Translate(TranslateError::UnsupportedPlatform(format!(
+                "未知平台: {}",
+                platform
+            )))
+        })?;
+    Span::current().record("platform", platform.as_ref());
+    Ok(platform)
+}
+
+pub async fn search_goods(
+    Query(params): Query<SearchParams>,
+    State(state): State<AppState>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
+) -> AppResult<Json<GoodsPage>> {
+    let platform = parse_platform(&params.platform)?;
+    let keyword = params.q.trim();
+    if keyword.is_empty() {
+        return Err(AppError::InvalidParams("搜索关键词不能为空".to_string()));
+    }
+    if params.page == 0 {
+        return Err(AppError::InvalidParams("page 从 1 开始".to_string()));
+    }
+    if params.page_size == 0 || params.page_size > MAX_PAGE_SIZE {
+        return Err(AppError::InvalidParams(format!(
+            "page_size 取值范围为 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    if let (Some(min), Some(max)) = (params.min_price, params.max_price)
+        && min > max
+    {
+        return Err(AppError::InvalidParams(
+            "min_price 不能大于 max_price".to_string(),
+        ));
+    }
+
+    let query = SearchQuery {
+        keyword: keyword.to_string(),
+        sort: params.sort,
+        page: params.page,
+        page_size: params.page_size,
+        with_coupon: params.with_coupon,
+        min_price: params.min_price,
+        max_price: params.max_price,
+        list_id: params.list_id,
+        // 推广位只能来自合作方密钥，不能由调用方指定
+        pid: api_key.and_then(|Extension(key)| key.pid),
+    };
+
+    let cache = state.feed_cache();
+    let key = format!("{}:search:{}", platform, query.cache_key());
+    if let Some(page) = cache.get(&key) {
+        return Ok(Json(page));
+    }
+
+    let translator = translator_for(platform, &state)?;
+    let page = translator.search_goods(&query).await?;
+
+    cache.insert(key, page.clone());
+    Ok(Json(page))
+}
+
+pub async fn goods_history(
+    Path((platform, goods_id)): Path<(String, String)>,
+    Query(query): Query<HistoryParams>,
+    State(state): State<AppState>,
+) -> AppResult<Json<GoodsHistory>> {
+    let platform = parse_platform(&platform)?;
+    if !(1..=MAX_HISTORY_LIMIT).contains(&query.limit) {
+        return Err(AppError::InvalidParams(format!(
+            "limit 取值范围为 1-{}",
+            MAX_HISTORY_LIMIT
+        )));
+    }
+
+    let db = state.connection_pool();
+    let observations: Vec<PriceObservation> =
+        price_history::find_by_goods(&db, platform, &goods_id, query.limit)
+            .await?
+            .into_iter()
+            .map(PriceObservation::from)
+            .collect();
+    let lowest_price = observations.iter().map(|o| o.price).min();
+
+    Ok(Json(GoodsHistory {
+        platform: platform.to_string(),
+        goods_id,
+        lowest_price,
+        observations,
+    }))
+}
@@ -0,0 +1,114 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use axum::{Json, extract::State, http::StatusCode};
+use serde::Serialize;
+
+use crate::{Platform, route::AppState};
+
+/// 数据库检查超时时间
+const DB_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub status: Status,
+    pub components: BTreeMap<String, ComponentHealth>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComponentHealth {
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ComponentHealth {
+    fn up() -> Self {
+        Self {
+            status: Status::Up,
+            detail: None,
+        }
+    }
+
+    fn down(detail: impl Into<String>) -> Self {
+        Self {
+            status: Status::Down,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+/// 存活检查，进程能响应即为存活
+pub async fn live() -> Json<HealthReport> {
+    Json(HealthReport {
+        status: Status::Up,
+        components: BTreeMap::new(),
+    })
+}
+
+/// 就绪检查，任一组件异常时返回 503
+///
+/// 各平台转链器的熔断状态只在 `translator:*` 组件的详情中报告，不影响就绪状态：所有实例
+/// 共用同一个上游，摘除实例无济于事。配置在启动和重新加载时都经过校验，运行中的配置总是有效的；重新加载失败时只在
+/// `config` 组件中报告原因，不影响就绪状态
+pub async fn ready(State(state): State<AppState>) -> (StatusCode, Json<HealthReport>) {
+    let mut components = BTreeMap::new();
+
+    components.insert("database".to_string(), check_database(&state).await);
+
+    for job in state.job_monitor().statuses() {
+        let health = if job.alive {
+            ComponentHealth::up()
+        } else {
+            ComponentHealth::down(format!("{} 秒未运行", job.last_beat_secs))
+        };
+        components.insert(format!("job:{}", job.name), health);
+    }
+
+    for platform in Platform::SUPPORTED {
+        let breaker = state.circuit_breakers().for_platform(platform);
+        components.insert(
+            format!("translator:{}", platform),
+            ComponentHealth {
+                status: Status::Up,
+                detail: Some(format!("熔断器状态: {}", breaker.state().as_ref())),
+            },
+        );
+    }
+
+    let config = match state.reload_error() {
+        None => ComponentHealth::up(),
+        Some(e) => ComponentHealth {
+            status: Status::Up,
+            detail: Some(format!("重新加载失败，继续使用当前配置: {}", e)),
+        },
+    };
+    components.insert("config".to_string(), config);
+
+    let status = if components.values().all(|c| c.status == Status::Up) {
+        Status::Up
+    } else {
+        Status::Down
+    };
+    let code = match status {
+        Status::Up => StatusCode::OK,
+        Status::Down => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    (code, Json(HealthReport { status, components }))
+}
+
+async fn check_database(state: &AppState) -> ComponentHealth {
+    let db = state.connection_pool();
+    match tokio::time::timeout(DB_CHECK_TIMEOUT, db.ping()).await {
+        Ok(Ok(())) => ComponentHealth::up(),
+        Ok(Err(e)) => ComponentHealth::down(e.to_string()),
+        Err(_) => ComponentHealth::down("连接超时"),
+    }
+}
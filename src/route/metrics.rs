@@ -0,0 +1,25 @@
+use axum::{extract::State, http::header};
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    error::{AppError, AppResult},
+    metrics,
+    route::AppState,
+};
+
+pub async fn export_metrics(
+    State(state): State<AppState>,
+) -> AppResult<([(header::HeaderName, &'static str); 1], String)> {
+    metrics::set_cache_entries("goods", state.goods_cache().size());
+    metrics::set_cache_entries("feed", state.feed_cache().size());
+    if let DatabaseConnection::SqlxPostgresPoolConnection(_) = state.connection_pool() {
+        let pool = state
+            .connection_pool()
+            .get_postgres_connection_pool()
+            .clone();
+        metrics::set_db_pool(pool.size(), pool.num_idle());
+    }
+
+    let body = metrics::encode().map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body))
+}
@@ -0,0 +1,97 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use lettre::message::Mailbox;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    api_key::ApiKeyIdentity,
+    entity::subscriptions,
+    error::{AppError, AppResult},
+    money::Money,
+    route::{AppState, goods::parse_platform},
+};
+
+/// 降价提醒订阅参数
+#[derive(Debug, Deserialize)]
+pub struct CreateSubscriptionParams {
+    email: String,
+    name: String,
+    platform: String,
+    goods_id: String,
+    /// 目标价，单位为元
+    target_price: Money,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionInfo {
+    pub id: Uuid,
+    pub email: String,
+    pub platform: String,
+    pub goods_id: String,
+    pub target_price: Money,
+    /// 订阅时间，秒级时间戳
+    pub subscribed_at: i64,
+}
+
+impl From<subscriptions::Model> for SubscriptionInfo {
+    fn from(model: subscriptions::Model) -> Self {
+        SubscriptionInfo {
+            id: model.id,
+            email: model.email,
+            platform: model.platform,
+            goods_id: model.goods_id,
+            target_price: Money::from_cents(model.target_price),
+            subscribed_at: model.subscribed_at.timestamp(),
+        }
+    }
+}
+
+/// 创建降价提醒订阅，必须携带有 `subscribe` 权限的 API 密钥
+pub async fn create_subscription(
+    State(state): State<AppState>,
+    identity: ApiKeyIdentity,
+    Json(params): Json<CreateSubscriptionParams>,
+) -> AppResult<(StatusCode, Json<SubscriptionInfo>)> {
+    let platform = parse_platform(&params.platform)?;
+    // 与发送邮件时的解析方式一致，避免保存无法发送的地址
+    if let Err(e) = params.email.parse::<Mailbox>() {
+        return Err(AppError::InvalidParams(format!(
+            "邮箱格式不正确: {}: {}",
+            params.email, e
+        )));
+    }
+    if !params.target_price.is_positive() {
+        return Err(AppError::InvalidParams("目标价必须大于零".to_string()));
+    }
+
+    let db = state.connection_pool();
+    let subscription = subscriptions::create(
+        &db,
+        identity.id,
+        &params.email,
+        &params.name,
+        platform,
+        &params.goods_id,
+        params.target_price,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(subscription.into())))
+}
+
+/// 删除订阅，只能删除同一密钥创建的订阅，其余订阅视为不存在
+pub async fn delete_subscription(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    identity: ApiKeyIdentity,
+) -> AppResult<StatusCode> {
+    let db = state.connection_pool();
+    if !subscriptions::delete(&db, identity.id, id).await? {
+        return Err(AppError::NotFound(format!("订阅 {}", id)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -2,19 +2,28 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Query, State},
 };
+use secrecy::SecretString;
 use serde::{Deserialize, Serialize};
-use tracing::warn;
+use strum::AsRefStr;
+use tracing::{Span, warn};
 
 use crate::{
     Platform,
+    api_key::ApiKeyIdentity,
+    circuit_breaker::CircuitBreakers,
+    configuration::ApplicationSettings,
+    entity::price_history,
     error::{AppError, AppResult, TranslateError, TranslateResult},
+    money::{Money, Rate},
     route::{AppState, translate::pdd::Pdd},
 };
 
+mod fixture;
 mod pdd;
+mod transport;
 
 /// 转链服务接口
 #[async_trait]
@@ -22,28 +31,218 @@ pub trait Translate: Send + Sync {
     /// 搜索商品信息
     async fn search(&self, url: &str) -> TranslateResult<GoodInfo>;
 
+    /// 根据商品 id 查询商品信息
+    async fn search_by_goods_id(&self, goods_id: &str) -> TranslateResult<GoodInfo>;
+
     /// 生成短链接
     async fn gen_short_url(&self, url: &str) -> anyhow::Result<String>;
+
+    /// 按关键词搜索商品
+    async fn search_goods(&self, _query: &SearchQuery) -> TranslateResult<GoodsPage> {
+        Err(TranslateError::UnsupportedPlatform(
+            "平台不支持关键词搜索".to_string(),
+        ))
+    }
+
+    /// 获取频道推荐商品，`channel_type` 含义由平台定义
+    async fn recommend(
+        &self,
+        _channel_type: i32,
+        _query: &FeedQuery,
+    ) -> TranslateResult<GoodsPage> {
+        Err(TranslateError::UnsupportedPlatform(
+            "平台不支持推荐商品".to_string(),
+        ))
+    }
+
+    /// 获取爆品榜单，`sort_type` 含义由平台定义
+    async fn top_goods(&self, _sort_type: i32, _query: &FeedQuery) -> TranslateResult<GoodsPage> {
+        Err(TranslateError::UnsupportedPlatform(
+            "平台不支持爆品榜单".to_string(),
+        ))
+    }
+
+    /// 按更新时间查询推广订单，时间为秒级时间戳，页码从 1 开始
+    async fn list_orders(
+        &self,
+        _updated_from: i64,
+        _updated_to: i64,
+        _page: u32,
+        _page_size: u32,
+    ) -> TranslateResult<OrderPage> {
+        Err(TranslateError::UnsupportedPlatform(
+            "平台不支持查询订单".to_string(),
+        ))
+    }
+
+    /// 按订单号查询推广订单
+    async fn order_detail(&self, _order_sn: &str) -> TranslateResult<OrderInfo> {
+        Err(TranslateError::UnsupportedPlatform(
+            "平台不支持查询订单".to_string(),
+        ))
+    }
+}
+
+/// 商品列表分页参数
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedQuery {
+    /// 翻页时带上上一页返回的 list_id，保证结果不重复
+    pub list_id: Option<String>,
+    #[serde(default)]
+    pub offset: u32,
+    #[serde(default = "default_feed_limit")]
+    pub limit: u32,
+    /// 推广位 id，不传时使用配置中的 pid
+    pub pid: Option<String>,
+}
+
+fn default_feed_limit() -> u32 {
+    20
+}
+
+impl FeedQuery {
+    /// 缓存键，不同分页和推广位分别缓存
+    pub fn cache_key(&self) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            self.list_id.as_deref().unwrap_or_default(),
+            self.offset,
+            self.limit,
+            self.pid.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+/// 搜索结果排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SearchSort {
+    /// 综合排序
+    #[default]
+    Default,
+    PriceAsc,
+    PriceDesc,
+    /// 按佣金比例
+    CommissionAsc,
+    CommissionDesc,
+    SalesAsc,
+    SalesDesc,
+}
+
+/// 关键词搜索参数
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    pub keyword: String,
+    pub sort: SearchSort,
+    /// 页码，从 1 开始
+    pub page: u32,
+    pub page_size: u32,
+    /// 只返回有券商品
+    pub with_coupon: bool,
+    /// 券后价下限
+    pub min_price: Option<Money>,
+    /// 券后价上限
+    pub max_price: Option<Money>,
+    pub list_id: Option<String>,
+    pub pid: Option<String>,
 }
 
-#[derive(Debug, Serialize, Default)]
+impl SearchQuery {
+    /// 缓存键，不同筛选条件和分页分别缓存
+    pub fn cache_key(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}:{}:{}:{}:{}",
+            self.keyword,
+            self.sort.as_ref(),
+            self.page,
+            self.page_size,
+            self.with_coupon,
+            self.min_price.map(|p| p.cents()).unwrap_or(-1),
+            self.max_price.map(|p| p.cents()).unwrap_or(-1),
+            self.list_id.as_deref().unwrap_or_default(),
+            self.pid.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+/// 商品列表分页结果
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct GoodsPage {
+    /// 翻页标识，请求下一页时原样传回
+    pub list_id: String,
+    pub total: i64,
+    pub goods: Vec<GoodInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
 pub struct GoodInfo {
-    /// 活动佣金比例，千分比（特定活动期间的佣金比例）
-    pub activity_promotion_rate: i64,
+    /// 商品 id，拼多多为 goods_sign
+    pub goods_id: String,
+    /// 商品名称
+    pub goods_name: String,
+    /// 商品缩略图
+    pub goods_thumbnail_url: String,
+    /// 商品轮播图
+    pub goods_gallery_urls: Vec<String>,
+    /// 店铺名称
+    pub mall_name: String,
+    /// 已售卖件数，如 "10万+"
+    pub sales_tip: String,
+    /// 活动佣金比例（特定活动期间的佣金比例）
+    pub activity_promotion_rate: Rate,
     /// 比价行为预判定佣金，需要用户备案
-    pub predict_promotion_rate: i64,
-    /// 佣金比例，千分比
-    pub promotion_rate: i64,
-    /// 优惠券面额，单位为分
-    pub coupon_discount: i64,
-    /// 优惠券后价格
-    pub coupon_discount_price: i64,
+    pub predict_promotion_rate: Rate,
+    /// 佣金比例
+    pub promotion_rate: Rate,
+    /// 预估佣金，按券后价计算
+    pub promotion_amount: Money,
+    /// 优惠券面额
+    pub coupon_discount: Money,
+    /// 优惠券后价格，未达到使用门槛时等于原价
+    pub coupon_discount_price: Money,
+    /// 优惠券生效时间，秒级时间戳
+    pub coupon_start_time: i64,
+    /// 优惠券失效时间，秒级时间戳
+    pub coupon_end_time: i64,
+    /// 优惠券剩余数量
+    pub coupon_remain_quantity: i64,
+    /// 优惠券使用门槛
+    pub coupon_min_order_amount: Money,
     /// 原价
-    pub origin_price: i64,
+    pub origin_price: Money,
     /// 转链后短链
     pub short_url: String,
 }
 
+/// 推广订单，金额按元序列化为字符串，如 `"9.00"`
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderInfo {
+    pub order_sn: String,
+    pub goods_id: String,
+    pub goods_name: String,
+    /// 推广位 id
+    pub pid: String,
+    /// 实际支付金额
+    pub order_amount: Money,
+    pub promotion_rate: Rate,
+    /// 预估佣金
+    pub promotion_amount: Money,
+    /// 订单状态，含义由平台定义
+    pub status: i32,
+    /// 下单时间，秒级时间戳
+    pub created_at: i64,
+    /// 最后更新时间，秒级时间戳
+    pub modified_at: i64,
+}
+
+/// 订单分页结果
+#[derive(Debug, Clone, Default)]
+pub struct OrderPage {
+    pub total: i64,
+    pub orders: Vec<OrderInfo>,
+}
+
 /// 转链请求参数
 #[derive(Debug, Deserialize)]
 pub struct TranslateLinkParams {
@@ -53,11 +252,39 @@ pub struct TranslateLinkParams {
 pub async fn translate_link(
     Query(query): Query<TranslateLinkParams>,
     State(state): State<AppState>,
+    api_key: Option<Extension<ApiKeyIdentity>>,
 ) -> AppResult<Json<GoodInfo>> {
     let url = query.url.as_str();
 
     // 获取适合的转链器
-    let translator = get_translator(url, &state)?;
+    let platform = identify_platform(url).ok_or_else(|| {
+        AppError::Translate(TranslateError::UnsupportedPlatform(
+            "平台暂不支持".to_string(),
+        ))
+    })?;
+    Span::current().record("platform", platform.as_ref());
+
+    // 合作方密钥绑定了推广位时用它转链，短链接与推广位相关，缓存也要分开
+    let pid = api_key.and_then(|Extension(key)| key.pid);
+    let cache_key = match &pid {
+        Some(pid) => format!("{}|{}", pid, url),
+        None => url.to_string(),
+    };
+    // 命中缓存时不记录价格：缓存内容是旧的观测结果，重复写入会伪造价格记录。
+    // 这些商品在未命中时已记录过，之后由价格刷新任务定期重新观测
+    let cache = state.goods_cache();
+    if let Some(good_info) = cache.get(&cache_key) {
+        return Ok(Json(good_info));
+    }
+
+    let translator = match &pid {
+        Some(pid) => {
+            let mut settings = state.app_settings();
+            settings.pdd.pid = SecretString::from(pid.clone());
+            translator_from_settings(platform, &settings, &state.circuit_breakers())?
+        }
+        None => translator_for(platform, &state)?,
+    };
 
     // 使用转链器搜索商品信息
     let mut good_info = translator
@@ -65,28 +292,46 @@ pub async fn translate_link(
         .await
         .map_err(|e| AppError::Unknown(format!("搜索商品失败: {}", e)))?;
 
+    // 后台记录价格，失败不影响转链
+    let db = state.connection_pool();
+    let (source_url, observed) = (url.to_string(), good_info.clone());
+    tokio::spawn(async move {
+        if let Err(e) =
+            price_history::record(&db, platform, &source_url, pid.as_deref(), &observed).await
+        {
+            warn!("记录商品价格失败: {}", e);
+        }
+    });
+
     // 生成短链接
     good_info.short_url = translator.gen_short_url(url).await.map_err(|e| {
         warn!("生成短链接失败: {}", e);
         AppError::Unknown(e.to_string())
     })?;
 
+    cache.insert(cache_key, good_info.clone());
     Ok(Json(good_info))
 }
 
-// 根据URL获取适合的转链器
-fn get_translator(url: &str, state: &AppState) -> AppResult<Arc<dyn Translate>> {
-    let platform = identify_platform(url).ok_or_else(|| {
-        AppError::Translate(TranslateError::UnsupportedPlatform(
-            "平台暂不支持".to_string(),
-        ))
-    })?;
+/// 根据平台获取转链器
+pub(crate) fn translator_for(
+    platform: Platform,
+    state: &AppState,
+) -> AppResult<Arc<dyn Translate>> {
+    translator_from_settings(platform, &state.app_settings(), &state.circuit_breakers())
+}
 
+/// 根据平台和配置创建转链器
+pub(crate) fn translator_from_settings(
+    platform: Platform,
+    settings: &ApplicationSettings,
+    breakers: &CircuitBreakers,
+) -> AppResult<Arc<dyn Translate>> {
     match platform {
-        Platform::Pdd => {
-            let settings = state.inner.lock().unwrap().app_settings.pdd.clone();
-            Ok(Arc::new(Pdd::new(settings)))
-        }
+        Platform::Pdd => Ok(Arc::new(Pdd::new(
+            settings.pdd.clone(),
+            breakers.for_platform(platform),
+        ))),
         // 后续可以添加其他平台支持
         Platform::Unknown => {
             warn!("未知平台");
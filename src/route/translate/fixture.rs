@@ -0,0 +1,190 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{
+    configuration::application::{FixtureMode, FixtureSettings},
+    error::{TranslateError, TranslateResult},
+    route::translate::transport::Transport,
+};
+
+/// 录制时脱敏的请求参数，这些参数也不参与匹配
+const REDACTED_PARAMS: [&str; 6] = [
+    "client_id",
+    "client_secret",
+    "sign",
+    "timestamp",
+    "pid",
+    "p_id",
+];
+
+const REDACTED: &str = "<redacted>";
+
+/// 一次上游请求及其响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Fixture {
+    pub api_type: String,
+    /// 请求参数，敏感参数已脱敏
+    pub params: BTreeMap<String, String>,
+    pub status: u16,
+    /// 响应体，是 JSON 时按结构保存便于手工修改，否则保存原文
+    pub body: Value,
+}
+
+impl Fixture {
+    /// 响应体原文
+    pub fn body_text(&self) -> String {
+        match &self.body {
+            Value::String(text) => text.clone(),
+            body => body.to_string(),
+        }
+    }
+}
+
+/// 按接口和业务参数存取录制数据的目录
+///
+/// 每个请求对应一个 `{api_type}-{key}.json` 文件，`key` 由去掉敏感参数后的
+/// 请求参数计算得到，因此回放时换一套凭证也能匹配
+#[derive(Debug, Clone)]
+pub struct FixtureStore {
+    dir: PathBuf,
+}
+
+impl FixtureStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// 写入录制数据，同一请求重复录制时覆盖
+    pub async fn record(
+        &self,
+        api_type: &str,
+        params: &HashMap<&str, &str>,
+        status: u16,
+        body: &str,
+    ) -> TranslateResult<()> {
+        let fixture = Fixture {
+            api_type: api_type.to_string(),
+            params: params
+                .iter()
+                .map(|(k, v)| {
+                    let v = if REDACTED_PARAMS.contains(k) {
+                        REDACTED
+                    } else {
+                        v
+                    };
+                    (k.to_string(), v.to_string())
+                })
+                .collect(),
+            status,
+            body: serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.to_string())),
+        };
+        let content = serde_json::to_vec_pretty(&fixture)
+            .map_err(|e| TranslateError::Internal(e.to_string()))?;
+
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| TranslateError::Internal(format!("创建录制目录失败: {}", e)))?;
+        let path = self.path(api_type, params);
+        tokio::fs::write(&path, content).await.map_err(|e| {
+            TranslateError::Internal(format!("写入录制数据 {} 失败: {}", path.display(), e))
+        })
+    }
+
+    /// 读取与请求匹配的录制数据
+    pub async fn replay(
+        &self,
+        api_type: &str,
+        params: &HashMap<&str, &str>,
+    ) -> TranslateResult<Fixture> {
+        let path = self.path(api_type, params);
+        let content = tokio::fs::read(&path).await.map_err(|e| {
+            TranslateError::Internal(format!("没有匹配的录制数据 {}: {}", path.display(), e))
+        })?;
+        serde_json::from_slice(&content).map_err(|e| {
+            TranslateError::Internal(format!("解析录制数据 {} 失败: {}", path.display(), e))
+        })
+    }
+
+    fn path(&self, api_type: &str, params: &HashMap<&str, &str>) -> PathBuf {
+        self.dir
+            .join(format!("{}-{}.json", api_type, fixture_key(params)))
+    }
+}
+
+/// 按录制配置包装网关请求
+pub fn transport(settings: FixtureSettings, inner: Arc<dyn Transport>) -> Arc<dyn Transport> {
+    let store = FixtureStore::new(settings.dir);
+    match settings.mode {
+        FixtureMode::Record => Arc::new(RecordingTransport { inner, store }),
+        FixtureMode::Replay => Arc::new(ReplayTransport { store }),
+    }
+}
+
+/// 请求网关并把响应写入录制目录，录制失败不影响请求
+#[derive(Debug)]
+struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    store: FixtureStore,
+}
+
+#[async_trait]
+impl Transport for RecordingTransport {
+    async fn send(
+        &self,
+        api_type: &str,
+        query: &HashMap<&str, &str>,
+    ) -> TranslateResult<(StatusCode, String)> {
+        let (status, text) = self.inner.send(api_type, query).await?;
+        if let Err(e) = self
+            .store
+            .record(api_type, query, status.as_u16(), &text)
+            .await
+        {
+            warn!("录制上游响应失败: {}", e);
+        }
+        Ok((status, text))
+    }
+}
+
+/// 只从录制目录读取响应，不请求网关
+#[derive(Debug)]
+struct ReplayTransport {
+    store: FixtureStore,
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn send(
+        &self,
+        api_type: &str,
+        query: &HashMap<&str, &str>,
+    ) -> TranslateResult<(StatusCode, String)> {
+        let fixture = self.store.replay(api_type, query).await?;
+        let status = StatusCode::from_u16(fixture.status)
+            .map_err(|e| TranslateError::Internal(e.to_string()))?;
+        Ok((status, fixture.body_text()))
+    }
+}
+
+/// 对参与匹配的参数排序后取 MD5
+fn fixture_key(params: &HashMap<&str, &str>) -> String {
+    let matched: BTreeMap<_, _> = params
+        .iter()
+        .filter(|(k, _)| !REDACTED_PARAMS.contains(k) && **k != "type")
+        .collect();
+    let canonical = matched
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{:x}", md5::compute(canonical))
+}
@@ -1,42 +1,64 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc, time::Instant};
 
 use anyhow::anyhow;
 use async_trait::async_trait;
 use axum::http::StatusCode;
 use chrono::Utc;
-use reqwest::Client;
 use secrecy::ExposeSecret;
-use serde::Deserialize;
-use tracing::info;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
 
 use crate::{
+    Platform,
+    circuit_breaker::CircuitBreaker,
     configuration::application::PddSettings,
     error::{TranslateError, TranslateResult},
-    route::translate::{GoodInfo, Translate},
+    metrics,
+    money::{Money, Rate},
+    route::translate::{
+        FeedQuery, GoodInfo, GoodsPage, OrderInfo, OrderPage, SearchQuery, SearchSort, Translate,
+        fixture,
+        transport::{HttpTransport, Transport},
+    },
     util::generate_signature,
 };
 
 #[derive(Debug)]
 pub struct Pdd {
-    client: reqwest::Client,
-    domain: String,
+    transport: Arc<dyn Transport>,
     client_id: String,
     client_secret: String,
     pid: String,
     api_good_search: String,
     api_gen_short_url: String,
+    api_goods_recommend: String,
+    api_top_goods: String,
+    api_promotion_url_generate: String,
+    api_order_list_increment: String,
+    api_order_detail: String,
+    breaker: Arc<CircuitBreaker>,
 }
 
 impl Pdd {
-    pub fn new(settings: PddSettings) -> Self {
+    pub fn new(settings: PddSettings, breaker: Arc<CircuitBreaker>) -> Self {
+        let http: Arc<dyn Transport> = Arc::new(HttpTransport::new(settings.domain));
+        let transport = match settings.fixtures {
+            Some(fixtures) => fixture::transport(fixtures, http),
+            None => http,
+        };
         Self {
-            client: Client::builder().build().unwrap(),
-            domain: settings.domain,
+            breaker,
+            transport,
             client_id: settings.client_id.expose_secret().to_string(),
             client_secret: settings.client_secret.expose_secret().to_string(),
             pid: settings.pid.expose_secret().to_string(),
             api_good_search: settings.api_good_search,
             api_gen_short_url: settings.api_gen_short_url,
+            api_goods_recommend: settings.api_goods_recommend,
+            api_top_goods: settings.api_top_goods,
+            api_promotion_url_generate: settings.api_promotion_url_generate,
+            api_order_list_increment: settings.api_order_list_increment,
+            api_order_detail: settings.api_order_detail,
         }
     }
 }
@@ -51,15 +73,42 @@ pub struct PddGoodsSearchResponse {
 #[derive(Debug, Deserialize)]
 pub struct GoodsSearchResponse {
     goods_list: Vec<GoodsItem>,
+    #[serde(default)]
+    list_id: Option<String>,
+    #[serde(default)]
+    total_count: i64,
 }
 
 /// 商品项
 #[derive(Debug, Deserialize)]
 pub struct GoodsItem {
     promotion_rate: i64,
+    #[serde(default)]
     predict_promotion_rate: i64,
     coupon_discount: i64,
     min_group_price: i64,
+    #[serde(default)]
+    goods_sign: String,
+    #[serde(default)]
+    goods_name: String,
+    #[serde(default)]
+    goods_thumbnail_url: String,
+    #[serde(default)]
+    goods_image_url: String,
+    #[serde(default)]
+    goods_gallery_urls: Option<Vec<String>>,
+    #[serde(default)]
+    mall_name: String,
+    #[serde(default)]
+    sales_tip: String,
+    #[serde(default)]
+    coupon_start_time: i64,
+    #[serde(default)]
+    coupon_end_time: i64,
+    #[serde(default)]
+    coupon_remain_quantity: i64,
+    #[serde(default)]
+    coupon_min_order_amount: i64,
     // 其他字段可以根据需要添加
 }
 
@@ -75,28 +124,272 @@ pub struct GoodsZsUnitGenerateResponse {
     short_url: String,
 }
 
+/// 拼多多搜索筛选区间
+#[derive(Debug, Serialize)]
+struct RangeItem {
+    /// 1-券后价
+    range_id: i32,
+    range_from: i64,
+    range_to: i64,
+}
+
+/// 券后价筛选区间的 range_id
+const RANGE_ID_COUPON_PRICE: i32 = 1;
+
+/// 转换为拼多多的 sort_type
+fn pdd_sort_type(sort: SearchSort) -> &'static str {
+    match sort {
+        SearchSort::Default => "0",
+        SearchSort::CommissionAsc => "1",
+        SearchSort::CommissionDesc => "2",
+        SearchSort::PriceAsc => "3",
+        SearchSort::PriceDesc => "4",
+        SearchSort::SalesAsc => "5",
+        SearchSort::SalesDesc => "6",
+    }
+}
+
+/// 拼多多推荐商品响应
+#[derive(Debug, Deserialize)]
+pub struct PddGoodsRecommendResponse {
+    goods_basic_detail_response: GoodsListResponse,
+}
+
+/// 拼多多爆品榜单响应
+#[derive(Debug, Deserialize)]
+pub struct PddTopGoodsListResponse {
+    top_goods_list_get_response: GoodsListResponse,
+}
+
+/// 商品列表响应内容
+#[derive(Debug, Deserialize)]
+pub struct GoodsListResponse {
+    #[serde(default)]
+    list: Vec<GoodsItem>,
+    #[serde(default)]
+    list_id: Option<String>,
+    #[serde(default)]
+    total: i64,
+}
+
+/// 拼多多推广链接生成响应
+#[derive(Debug, Deserialize)]
+pub struct PddGoodsPromotionUrlGenerateResponse {
+    goods_promotion_url_generate_response: GoodsPromotionUrlGenerateResponse,
+}
+
+/// 推广链接生成响应内容
+#[derive(Debug, Deserialize)]
+pub struct GoodsPromotionUrlGenerateResponse {
+    goods_promotion_url_list: Vec<GoodsPromotionUrl>,
+}
+
+/// 推广链接
+#[derive(Debug, Deserialize)]
+pub struct GoodsPromotionUrl {
+    short_url: String,
+}
+
+/// 拼多多增量订单响应
+#[derive(Debug, Deserialize)]
+pub struct PddOrderListIncrementResponse {
+    order_list_get_response: OrderListResponse,
+}
+
+/// 订单列表响应内容
+#[derive(Debug, Deserialize)]
+pub struct OrderListResponse {
+    #[serde(default)]
+    order_list: Vec<OrderItem>,
+    #[serde(default)]
+    total_count: i64,
+}
+
+/// 拼多多订单详情响应
+#[derive(Debug, Deserialize)]
+pub struct PddOrderDetailResponse {
+    order_detail_response: OrderItem,
+}
+
+/// 订单项
+#[derive(Debug, Deserialize)]
+pub struct OrderItem {
+    order_sn: String,
+    #[serde(default)]
+    goods_id: i64,
+    #[serde(default)]
+    goods_sign: String,
+    #[serde(default)]
+    goods_name: String,
+    #[serde(default)]
+    p_id: String,
+    #[serde(default)]
+    order_amount: i64,
+    #[serde(default)]
+    promotion_rate: i64,
+    #[serde(default)]
+    promotion_amount: i64,
+    /// 0-已支付；1-已成团；2-确认收货；3-审核成功；4-审核失败；5-已经结算；10-已处罚
+    order_status: i32,
+    #[serde(default)]
+    order_create_time: i64,
+    #[serde(default)]
+    order_modify_at: i64,
+}
+
+impl From<OrderItem> for OrderInfo {
+    fn from(item: OrderItem) -> Self {
+        // 与商品接口保持一致，优先使用 goods_sign
+        let goods_id = if item.goods_sign.is_empty() {
+            item.goods_id.to_string()
+        } else {
+            item.goods_sign
+        };
+        OrderInfo {
+            order_sn: item.order_sn,
+            goods_id,
+            goods_name: item.goods_name,
+            pid: item.p_id,
+            order_amount: Money::from_cents(item.order_amount),
+            promotion_rate: Rate::from_permille(item.promotion_rate),
+            promotion_amount: Money::from_cents(item.promotion_amount),
+            status: item.order_status,
+            created_at: item.order_create_time,
+            modified_at: item.order_modify_at,
+        }
+    }
+}
+
 impl From<&GoodsItem> for GoodInfo {
     fn from(item: &GoodsItem) -> Self {
-        // 计算优惠券后价格 = 原价 - 优惠券面额
-        let coupon_discount_price = item.min_group_price - item.coupon_discount;
+        let origin_price = Money::from_cents(item.min_group_price);
+        let coupon_discount = Money::from_cents(item.coupon_discount);
+        let coupon_min_order_amount = Money::from_cents(item.coupon_min_order_amount);
+        let promotion_rate = Rate::from_permille(item.promotion_rate);
+
+        // 计算优惠券后价格，未达到使用门槛时按原价计算
+        let coupon_discount_price =
+            origin_price.after_coupon(coupon_discount, coupon_min_order_amount);
+        // 预估佣金 = 券后价 * 佣金比例
+        let promotion_amount = coupon_discount_price
+            .checked_mul_rate(promotion_rate)
+            .unwrap_or_default();
+
+        // 搜索接口不一定返回轮播图，缺省时用主图兜底
+        let goods_gallery_urls = match &item.goods_gallery_urls {
+            Some(urls) if !urls.is_empty() => urls.clone(),
+            _ if !item.goods_image_url.is_empty() => vec![item.goods_image_url.clone()],
+            _ => vec![],
+        };
 
         GoodInfo {
-            promotion_rate: item.promotion_rate,
-            predict_promotion_rate: item.predict_promotion_rate,
-            coupon_discount: item.coupon_discount,
+            goods_id: item.goods_sign.clone(),
+            goods_name: item.goods_name.clone(),
+            goods_thumbnail_url: item.goods_thumbnail_url.clone(),
+            goods_gallery_urls,
+            mall_name: item.mall_name.clone(),
+            sales_tip: item.sales_tip.clone(),
+            promotion_rate,
+            predict_promotion_rate: Rate::from_permille(item.predict_promotion_rate),
+            promotion_amount,
+            coupon_discount,
             coupon_discount_price,
-            origin_price: item.min_group_price,
+            coupon_start_time: item.coupon_start_time,
+            coupon_end_time: item.coupon_end_time,
+            coupon_remain_quantity: item.coupon_remain_quantity,
+            coupon_min_order_amount,
+            origin_price,
             // 其他字段设置为默认值
-            activity_promotion_rate: 0,
+            activity_promotion_rate: Rate::default(),
             short_url: String::new(),
         }
     }
 }
 impl Pdd {
+    /// 将商品列表转换为分页结果，并为每个商品生成对应推广位的推广链接
+    async fn build_goods_page(&self, response: GoodsListResponse, pid: &str) -> GoodsPage {
+        let mut goods: Vec<GoodInfo> = response.list.iter().map(GoodInfo::from).collect();
+
+        let goods_signs: Vec<&str> = goods.iter().map(|g| g.goods_id.as_str()).collect();
+        match self.gen_promotion_urls(&goods_signs, pid).await {
+            // 接口按请求顺序返回推广链接
+            Ok(urls) if urls.len() == goods.len() => {
+                for (good, url) in goods.iter_mut().zip(urls) {
+                    good.short_url = url;
+                }
+            }
+            Ok(urls) => warn!(
+                "推广链接数量不匹配: 期望 {}，实际 {}",
+                goods.len(),
+                urls.len()
+            ),
+            Err(e) => warn!("生成推广链接失败: {}", e),
+        }
+
+        GoodsPage {
+            list_id: response.list_id.unwrap_or_default(),
+            total: response.total,
+            goods,
+        }
+    }
+
+    /// 批量生成商品推广短链
+    async fn gen_promotion_urls(
+        &self,
+        goods_signs: &[&str],
+        pid: &str,
+    ) -> TranslateResult<Vec<String>> {
+        if goods_signs.is_empty() {
+            return Ok(vec![]);
+        }
+        let goods_sign_list = serde_json::to_string(goods_signs)
+            .map_err(|e| TranslateError::Internal(e.to_string()))?;
+        let mut params = HashMap::new();
+        params.insert("goods_sign_list", goods_sign_list.as_str());
+        params.insert("p_id", pid);
+        params.insert("generate_short_url", "true");
+
+        let response: PddGoodsPromotionUrlGenerateResponse = self
+            .make_request(&self.api_promotion_url_generate, params)
+            .await?;
+
+        Ok(response
+            .goods_promotion_url_generate_response
+            .goods_promotion_url_list
+            .into_iter()
+            .map(|u| u.short_url)
+            .collect())
+    }
+
     async fn make_request<T: for<'de> serde::Deserialize<'de>>(
         &self,
         api_type: &str,
         params: HashMap<&str, &str>,
+    ) -> TranslateResult<T> {
+        let breaker = &self.breaker;
+        if !breaker.allow() {
+            return Err(TranslateError::Request("上游接口熔断中".to_string()));
+        }
+
+        let start = Instant::now();
+        let result = self.send_request(api_type, params).await;
+        match &result {
+            Err(e) if e.is_upstream_failure() => breaker.record_failure(),
+            _ => breaker.record_success(),
+        }
+        metrics::record_upstream_request(
+            Platform::Pdd.as_ref(),
+            api_type,
+            result.is_ok(),
+            start.elapsed(),
+        );
+        result
+    }
+
+    async fn send_request<T: for<'de> serde::Deserialize<'de>>(
+        &self,
+        api_type: &str,
+        params: HashMap<&str, &str>,
     ) -> TranslateResult<T> {
         let timestamp = Utc::now().timestamp().to_string();
         // 构建基础参数
@@ -116,26 +409,13 @@ impl Pdd {
         let sign = generate_signature(body.clone(), &self.client_secret);
         body.insert("sign", sign.as_str());
 
-        // 发送请求
-        let res = self
-            .client
-            .get(self.domain.as_str())
-            .query(&body)
-            .send()
-            .await
-            .map_err(|e| TranslateError::Request(e.to_string()))?;
-
-        if res.status() != StatusCode::OK {
-            return Err(TranslateError::Internal(format!(
-                "Status code: {}",
-                res.status()
-            )));
+        let (status, text) = self.transport.send(api_type, &body).await?;
+        if status != StatusCode::OK {
+            return Err(TranslateError::Status(status.as_u16()));
         }
 
         // 解析响应
-        let response = res
-            .json::<T>()
-            .await
+        let response = serde_json::from_str::<T>(&text)
             .map_err(|e| TranslateError::Internal(format!("解析响应失败: {}", e)))?;
 
         Ok(response)
@@ -163,6 +443,26 @@ impl Translate for Pdd {
         Ok(good.into())
     }
 
+    async fn search_by_goods_id(&self, goods_id: &str) -> TranslateResult<GoodInfo> {
+        let goods_sign_list = serde_json::to_string(&[goods_id])
+            .map_err(|e| TranslateError::Internal(e.to_string()))?;
+        let mut params = HashMap::new();
+        params.insert("goods_sign_list", goods_sign_list.as_str());
+        params.insert("pid", self.pid.as_str());
+
+        let response: PddGoodsSearchResponse = self
+            .make_request(self.api_good_search.as_str(), params)
+            .await?;
+
+        let good = response
+            .goods_search_response
+            .goods_list
+            .first()
+            .ok_or_else(|| TranslateError::Internal("未找到商品".to_string()))?;
+
+        Ok(good.into())
+    }
+
     async fn gen_short_url(&self, url: &str) -> anyhow::Result<String> {
         info!("生成短链接: {}", url);
 
@@ -179,4 +479,139 @@ impl Translate for Pdd {
 
         Ok(response.goods_zs_unit_generate_response.short_url)
     }
+
+    async fn search_goods(&self, query: &SearchQuery) -> TranslateResult<GoodsPage> {
+        let pid = query.pid.as_deref().unwrap_or(self.pid.as_str());
+        let page = query.page.to_string();
+        let page_size = query.page_size.to_string();
+        let with_coupon = query.with_coupon.to_string();
+        let range_list = match (query.min_price, query.max_price) {
+            (None, None) => None,
+            (min, max) => Some(
+                serde_json::to_string(&[RangeItem {
+                    range_id: RANGE_ID_COUPON_PRICE,
+                    range_from: min.unwrap_or(Money::ZERO).cents(),
+                    range_to: max.map_or(i64::MAX, |p| p.cents()),
+                }])
+                .map_err(|e| TranslateError::Internal(e.to_string()))?,
+            ),
+        };
+
+        let mut params = HashMap::new();
+        params.insert("keyword", query.keyword.as_str());
+        params.insert("sort_type", pdd_sort_type(query.sort));
+        params.insert("page", page.as_str());
+        params.insert("page_size", page_size.as_str());
+        params.insert("with_coupon", with_coupon.as_str());
+        params.insert("pid", pid);
+        if let Some(range_list) = &range_list {
+            params.insert("range_list", range_list.as_str());
+        }
+        if let Some(list_id) = &query.list_id {
+            params.insert("list_id", list_id.as_str());
+        }
+
+        let response: PddGoodsSearchResponse = self
+            .make_request(self.api_good_search.as_str(), params)
+            .await?;
+        let response = response.goods_search_response;
+
+        Ok(self
+            .build_goods_page(
+                GoodsListResponse {
+                    list: response.goods_list,
+                    list_id: response.list_id,
+                    total: response.total_count,
+                },
+                pid,
+            )
+            .await)
+    }
+
+    async fn recommend(&self, channel_type: i32, query: &FeedQuery) -> TranslateResult<GoodsPage> {
+        let pid = query.pid.as_deref().unwrap_or(self.pid.as_str());
+        let channel_type = channel_type.to_string();
+        let offset = query.offset.to_string();
+        let limit = query.limit.to_string();
+
+        let mut params = HashMap::new();
+        params.insert("channel_type", channel_type.as_str());
+        params.insert("offset", offset.as_str());
+        params.insert("limit", limit.as_str());
+        params.insert("pid", pid);
+        if let Some(list_id) = &query.list_id {
+            params.insert("list_id", list_id.as_str());
+        }
+
+        let response: PddGoodsRecommendResponse =
+            self.make_request(&self.api_goods_recommend, params).await?;
+
+        Ok(self
+            .build_goods_page(response.goods_basic_detail_response, pid)
+            .await)
+    }
+
+    async fn top_goods(&self, sort_type: i32, query: &FeedQuery) -> TranslateResult<GoodsPage> {
+        let pid = query.pid.as_deref().unwrap_or(self.pid.as_str());
+        let sort_type = sort_type.to_string();
+        let offset = query.offset.to_string();
+        let limit = query.limit.to_string();
+
+        let mut params = HashMap::new();
+        params.insert("sort_type", sort_type.as_str());
+        params.insert("offset", offset.as_str());
+        params.insert("limit", limit.as_str());
+        params.insert("p_id", pid);
+        if let Some(list_id) = &query.list_id {
+            params.insert("list_id", list_id.as_str());
+        }
+
+        let response: PddTopGoodsListResponse =
+            self.make_request(&self.api_top_goods, params).await?;
+
+        Ok(self
+            .build_goods_page(response.top_goods_list_get_response, pid)
+            .await)
+    }
+
+    async fn list_orders(
+        &self,
+        updated_from: i64,
+        updated_to: i64,
+        page: u32,
+        page_size: u32,
+    ) -> TranslateResult<OrderPage> {
+        let (updated_from, updated_to) = (updated_from.to_string(), updated_to.to_string());
+        let (page, page_size) = (page.to_string(), page_size.to_string());
+
+        let mut params = HashMap::new();
+        params.insert("start_update_time", updated_from.as_str());
+        params.insert("end_update_time", updated_to.as_str());
+        params.insert("page", page.as_str());
+        params.insert("page_size", page_size.as_str());
+        params.insert("return_count", "true");
+
+        let response: PddOrderListIncrementResponse = self
+            .make_request(&self.api_order_list_increment, params)
+            .await?;
+        let response = response.order_list_get_response;
+
+        Ok(OrderPage {
+            total: response.total_count,
+            orders: response
+                .order_list
+                .into_iter()
+                .map(OrderInfo::from)
+                .collect(),
+        })
+    }
+
+    async fn order_detail(&self, order_sn: &str) -> TranslateResult<OrderInfo> {
+        let mut params = HashMap::new();
+        params.insert("order_sn", order_sn);
+
+        let response: PddOrderDetailResponse =
+            self.make_request(&self.api_order_detail, params).await?;
+        Ok(response.order_detail_response.into())
+    }
 }
@@ -0,0 +1,65 @@
+use std::{collections::HashMap, fmt::Debug};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use reqwest::Client;
+
+use crate::{
+    error::{TranslateError, TranslateResult},
+    middleware::{REQUEST_ID_HEADER, current_request_id},
+};
+
+/// 向上游网关发送已签名的请求
+///
+/// 录制和回放通过包装实现，转链器本身不关心请求从哪里得到响应
+#[async_trait]
+pub trait Transport: Send + Sync + Debug {
+    /// 发送请求，返回状态码和响应体原文
+    async fn send(
+        &self,
+        api_type: &str,
+        query: &HashMap<&str, &str>,
+    ) -> TranslateResult<(StatusCode, String)>;
+}
+
+/// 通过 HTTP 请求网关
+#[derive(Debug)]
+pub struct HttpTransport {
+    client: Client,
+    domain: String,
+}
+
+impl HttpTransport {
+    pub fn new(domain: String) -> Self {
+        Self {
+            client: Client::builder().build().unwrap(),
+            domain,
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(
+        &self,
+        _api_type: &str,
+        query: &HashMap<&str, &str>,
+    ) -> TranslateResult<(StatusCode, String)> {
+        // 附带请求 id 便于和网关日志关联
+        let mut request = self.client.get(self.domain.as_str()).query(query);
+        if let Some(request_id) = current_request_id() {
+            request = request.header(REQUEST_ID_HEADER, request_id);
+        }
+        let res = request
+            .send()
+            .await
+            .map_err(|e| TranslateError::Request(e.to_string()))?;
+
+        let status = res.status();
+        let text = res
+            .text()
+            .await
+            .map_err(|e| TranslateError::Request(e.to_string()))?;
+        Ok((status, text))
+    }
+}
@@ -1,45 +1,240 @@
-use std::io::Error;
+use std::{future::Future, io::Error, pin::Pin, time::Duration};
 
+use anyhow::Context;
 use axum::{Router, serve::Serve};
 use sea_orm::{ConnectOptions, Database, DatabaseConnection};
-use tokio::net::TcpListener;
+use tokio::{
+    net::TcpListener,
+    signal,
+    task::JoinHandle,
+    time::{Instant, timeout_at},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
 
 use crate::{
-    configuration,
+    configuration::{self, LoadOptions, application::ApplicationSettings},
+    job, notify, reload,
     route::{AppState, get_router},
+    startup::listener::TlsListener,
+    telemetry::LogFilters,
 };
 
+mod listener;
+
+/// 按配置选择的监听方式
+enum Server {
+    Tcp(Serve<TcpListener, Router, Router>),
+    Tls(Serve<TlsListener, Router, Router>),
+    #[cfg(unix)]
+    Unix(Serve<tokio::net::UnixListener, Router, Router>),
+}
+
+type ServeFuture = Pin<Box<dyn Future<Output = Result<(), Error>> + Send>>;
+
+impl Server {
+    async fn bind(settings: &ApplicationSettings, router: Router) -> anyhow::Result<(Self, u16)> {
+        if let Some(path) = &settings.unix_socket {
+            if settings.tls.is_some() {
+                anyhow::bail!("unix_socket 与 tls 不能同时配置");
+            }
+            return Self::bind_unix(path, router);
+        }
+
+        let addr = format!("{}:{}", settings.host, settings.port);
+        let listener = TcpListener::bind(&addr)
+            .await
+            .with_context(|| format!("监听 {} 失败", addr))?;
+        // 配置端口为 0 时以系统实际分配的端口为准
+        let port = listener.local_addr()?.port();
+        let server = match &settings.tls {
+            Some(tls) => {
+                info!("监听 https://{}:{}", settings.host, port);
+                Server::Tls(axum::serve(TlsListener::new(listener, tls)?, router))
+            }
+            None => {
+                info!("监听 http://{}:{}", settings.host, port);
+                Server::Tcp(axum::serve(listener, router))
+            }
+        };
+        Ok((server, port))
+    }
+
+    #[cfg(unix)]
+    fn bind_unix(path: &str, router: Router) -> anyhow::Result<(Self, u16)> {
+        use std::os::unix::fs::FileTypeExt;
+
+        // 清理上次异常退出遗留的套接字文件，其他类型的文件不动
+        if let Ok(metadata) = std::fs::symlink_metadata(path)
+            && metadata.file_type().is_socket()
+        {
+            std::fs::remove_file(path)?;
+        }
+        let listener = tokio::net::UnixListener::bind(path)
+            .with_context(|| format!("监听 Unix 套接字 {} 失败", path))?;
+        info!("监听 unix:{}", path);
+        Ok((Server::Unix(axum::serve(listener, router)), 0))
+    }
+
+    #[cfg(not(unix))]
+    fn bind_unix(_path: &str, _router: Router) -> anyhow::Result<(Self, u16)> {
+        anyhow::bail!("当前平台不支持 Unix 套接字")
+    }
+
+    fn with_graceful_shutdown(self, shutdown: CancellationToken) -> ServeFuture {
+        let signal = shutdown.cancelled_owned();
+        match self {
+            Server::Tcp(s) => Box::pin(s.with_graceful_shutdown(signal).into_future()),
+            Server::Tls(s) => Box::pin(s.with_graceful_shutdown(signal).into_future()),
+            #[cfg(unix)]
+            Server::Unix(s) => Box::pin(s.with_graceful_shutdown(signal).into_future()),
+        }
+    }
+}
+
 pub struct Application {
     port: u16,
-    server: Serve<TcpListener, Router, Router>,
+    server: Server,
+    state: AppState,
+    shutdown: CancellationToken,
+    shutdown_timeout: Duration,
+    jobs: Vec<JoinHandle<()>>,
 }
 
 impl Application {
-    pub async fn build(config: configuration::Settings) -> anyhow::Result<Application> {
+    pub async fn build(
+        config: configuration::Settings,
+        log_filters: LogFilters,
+    ) -> anyhow::Result<Application> {
         let connection_pool = get_connection_pool(config.db.build()).await?;
-        let app_state = AppState::new(connection_pool, config.application.clone());
-        let router = get_router(app_state);
+        let app_state = AppState::new(connection_pool, config.application.clone(), log_filters);
+
+        let shutdown = CancellationToken::new();
+        let notifier = notify::build_notifier(&config.application.price_alert.notifier)?;
+        let jobs = vec![
+            job::spawn_price_refresher(app_state.clone(), shutdown.child_token()),
+            job::spawn_price_alert(app_state.clone(), notifier, shutdown.child_token()),
+            job::spawn_rejection_flusher(app_state.clone(), shutdown.child_token()),
+        ];
+        let router = get_router(app_state.clone());
 
-        let port = config.application.port;
-        let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
+        let (server, port) = Server::bind(&config.application, router).await?;
 
-        let server = axum::serve(listener, router);
+        Ok(Self {
+            port,
+            server,
+            state: app_state,
+            shutdown,
+            shutdown_timeout: Duration::from_secs(config.application.shutdown_timeout_secs),
+            jobs,
+        })
+    }
 
-        Ok(Self { port, server })
+    /// 配置文件变化或收到 SIGHUP 时热加载配置，`source` 为启动时加载的原始配置
+    pub fn watch_config(
+        &mut self,
+        options: LoadOptions,
+        source: config::Config,
+    ) -> anyhow::Result<()> {
+        let reloader = reload::spawn_config_reloader(
+            self.state.clone(),
+            options,
+            source,
+            self.shutdown.child_token(),
+        )?;
+        self.jobs.push(reloader);
+        Ok(())
     }
 
+    /// 运行直到收到 SIGINT/SIGTERM 或 [`Application::shutdown_token`] 被取消
+    ///
+    /// 退出时停止接收新连接，等待进行中的请求和后台任务结束，超过
+    /// `shutdown_timeout_secs` 仍未结束则直接返回
     pub async fn run_until_stopped(self) -> std::result::Result<(), Error> {
-        self.server.await
+        let shutdown = self.shutdown.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_signal() => {
+                    info!("收到退出信号，开始优雅退出");
+                    shutdown.cancel();
+                }
+                _ = shutdown.cancelled() => {}
+            }
+        });
+
+        let mut server = self.server.with_graceful_shutdown(self.shutdown.clone());
+        let stopped = tokio::select! {
+            result = &mut server => {
+                result?;
+                true
+            }
+            _ = self.shutdown.cancelled() => false,
+        };
+
+        // 从收到退出信号开始计时，排空请求和等待后台任务共用同一个截止时间
+        let deadline = Instant::now() + self.shutdown_timeout;
+        if !stopped {
+            match timeout_at(deadline, server).await {
+                Ok(result) => result?,
+                Err(_) => warn!("等待请求结束超时，强制退出"),
+            }
+        }
+
+        // 服务器因错误退出时也要通知后台任务
+        self.shutdown.cancel();
+        let jobs = async {
+            for job in self.jobs {
+                if let Err(e) = job.await {
+                    warn!("后台任务异常退出: {}", e);
+                }
+            }
+        };
+        if timeout_at(deadline, jobs).await.is_err() {
+            warn!("等待后台任务结束超时，强制退出");
+        }
+        info!("服务已停止");
+
+        Ok(())
     }
 
+    /// 实际监听的端口，配置端口为 0 时为系统分配的端口；监听 Unix 套接字时为 0
     pub fn port(&self) -> u16 {
         self.port
     }
+
+    /// 取消该 token 即触发优雅退出
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
 }
 
 async fn get_connection_pool(opts: ConnectOptions) -> anyhow::Result<DatabaseConnection> {
-    return Ok(DatabaseConnection::default())
-    // Database::connect(opts)
-    //     .await
-    //     .map_err(|e| anyhow::anyhow!(e))
+    Database::connect(opts)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+}
+
+/// 等待 Ctrl+C 或 SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 }
@@ -0,0 +1,121 @@
+use std::{io, net::SocketAddr, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use axum::serve::Listener;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    task::JoinHandle,
+};
+use tokio_rustls::{
+    TlsAcceptor,
+    rustls::{
+        ServerConfig,
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+    },
+    server::TlsStream,
+};
+use tracing::{debug, error};
+
+use crate::configuration::application::TlsSettings;
+
+/// TLS 握手超时时间，避免慢连接长期占用资源
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 等待完成握手的连接队列长度
+const ACCEPT_BACKLOG: usize = 128;
+
+/// 在 TCP 之上终止 TLS 的监听器
+///
+/// 握手在后台任务中并发进行，单个慢连接不会阻塞其他连接的接入
+pub struct TlsListener {
+    local_addr: SocketAddr,
+    incoming: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+    acceptor_task: JoinHandle<()>,
+}
+
+impl TlsListener {
+    pub fn new(listener: TcpListener, settings: &TlsSettings) -> anyhow::Result<Self> {
+        let acceptor = TlsAcceptor::from(Arc::new(load_server_config(settings)?));
+        let local_addr = listener.local_addr()?;
+        let (tx, incoming) = mpsc::channel(ACCEPT_BACKLOG);
+        let acceptor_task = tokio::spawn(accept_loop(listener, acceptor, tx));
+        Ok(Self {
+            local_addr,
+            incoming,
+            acceptor_task,
+        })
+    }
+}
+
+impl Drop for TlsListener {
+    fn drop(&mut self) {
+        self.acceptor_task.abort();
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.incoming.recv().await {
+            Some(conn) => conn,
+            // 后台任务只会在监听器被丢弃时退出
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+async fn accept_loop(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    tx: mpsc::Sender<(TlsStream<TcpStream>, SocketAddr)>,
+) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                // 通常是文件描述符耗尽，稍后重试
+                error!("接收连接失败: {}", e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+        let (acceptor, tx) = (acceptor.clone(), tx.clone());
+        tokio::spawn(async move {
+            match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => {
+                    let _ = tx.send((stream, peer)).await;
+                }
+                Ok(Err(e)) => debug!("TLS 握手失败 {}: {}", peer, e),
+                Err(_) => debug!("TLS 握手超时 {}", peer),
+            }
+        });
+    }
+}
+
+/// 读取证书和私钥，构建 TLS 服务端配置
+fn load_server_config(settings: &TlsSettings) -> anyhow::Result<ServerConfig> {
+    let certs = CertificateDer::pem_file_iter(&settings.cert_path)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("读取证书失败: {}", settings.cert_path))?;
+    if certs.is_empty() {
+        anyhow::bail!("证书文件中没有证书: {}", settings.cert_path);
+    }
+    let key = PrivateKeyDer::from_pem_file(&settings.key_path)
+        .with_context(|| format!("读取私钥失败: {}", settings.key_path))?;
+
+    let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("证书与私钥不匹配")?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(config)
+}
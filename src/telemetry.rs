@@ -1,101 +1,226 @@
-use tracing::{Subscriber, level_filters::LevelFilter, subscriber::set_global_default};
-use tracing_appender::{non_blocking::WorkerGuard, rolling::RollingFileAppender};
+use anyhow::Context;
+use opentelemetry::{KeyValue, trace::TracerProvider as _};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource, runtime,
+    trace::{Sampler, SdkTracerProvider, span_processor_with_async_runtime::BatchSpanProcessor},
+};
+use tracing::{Subscriber, subscriber::set_global_default, warn};
+use tracing_appender::{
+    non_blocking::{NonBlocking, WorkerGuard},
+    rolling::RollingFileAppender,
+};
 use tracing_error::ErrorLayer;
 use tracing_log::LogTracer;
 use tracing_subscriber::{
-    Layer as _, Registry,
-    filter::Filtered,
-    fmt::{
-        format::{Compact, Format, Json},
-        time::{ChronoLocal, FormatTime},
-    },
-    layer::SubscriberExt,
+    EnvFilter, Layer, Registry, fmt::time::ChronoLocal, layer::SubscriberExt, reload,
+};
+
+use crate::configuration::{
+    LogSettings,
+    logging::{LogFormat, OtlpProtocol, OtlpSettings, Target, TargetKind},
 };
 
-use crate::configuration::{self, LogSettings};
+mod log_filter;
+mod retention;
+
+pub use log_filter::{LogFilterError, LogFilterInfo, LogFilters};
 
 const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
-type FilteredLayer = Filtered<
-    tracing_subscriber::fmt::Layer<
-        Registry,
-        tracing_subscriber::fmt::format::DefaultFields,
-        Format<Compact, ChronoLocal>,
-        tracing_appender::non_blocking::NonBlocking,
-    >,
-    LevelFilter,
-    Registry,
->;
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
 
 pub fn set_subscriber(subscriber: impl Subscriber + Send + Sync + 'static) {
     LogTracer::init().expect("Failed to set logger");
     set_global_default(subscriber).expect("Failed to set subscriber"); // 用 .init() 会报错
 }
 
+/// 日志和链路追踪的后台资源，析构时刷新尚未输出的日志和 span
+pub struct TelemetryGuard {
+    _workers: Vec<WorkerGuard>,
+    tracer_provider: Option<SdkTracerProvider>,
+    log_filters: LogFilters,
+}
+
+impl TelemetryGuard {
+    /// 各输出目标的过滤指令，用于运行时调整日志级别
+    pub fn log_filters(&self) -> LogFilters {
+        self.log_filters.clone()
+    }
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.tracer_provider.take()
+            && let Err(e) = provider.shutdown()
+        {
+            // 日志写入线程在此之后才关闭，这里的日志仍会输出
+            warn!("关闭链路追踪导出失败: {}", e);
+        }
+    }
+}
+
 pub fn init_tracing(
     config: LogSettings,
-) -> (impl Subscriber + Send + Sync + 'static, Vec<WorkerGuard>) {
-    let format = create_format();
-    let (layers, guards) = create_layers(config, format);
+) -> anyhow::Result<(impl Subscriber + Send + Sync + 'static, TelemetryGuard)> {
+    let log_filters = LogFilters::default();
+    let (mut layers, workers) = create_layers(&config, &log_filters)?;
+    let tracer_provider = match &config.otlp {
+        Some(otlp) => {
+            let (layer, provider) = create_otlp_layer(otlp)?;
+            layers.push(layer);
+            Some(provider)
+        }
+        None => None,
+    };
     let subscriber = Registry::default().with(layers).with(ErrorLayer::default());
-    (subscriber, guards)
+    Ok((
+        subscriber,
+        TelemetryGuard {
+            _workers: workers,
+            tracer_provider,
+            log_filters,
+        },
+    ))
 }
 
-fn create_layers<T, F>(
-    config: LogSettings,
-    format: Format<T, F>,
-) -> (Vec<FilteredLayer>, Vec<WorkerGuard>)
-where
-    T: Clone,
-    F: Clone + FormatTime + 'static,
-{
+fn create_layers(
+    config: &LogSettings,
+    log_filters: &LogFilters,
+) -> anyhow::Result<(Vec<BoxedLayer>, Vec<WorkerGuard>)> {
     let mut guards = vec![];
     let mut layers = vec![];
-    for c in config.targets {
-        let guard;
-        let layer;
-        let level_filter = LevelFilter::from_level(c.level);
-        match c.kind {
-            configuration::logging::TargetKind::Stdout => {
+    for c in &config.targets {
+        let directive = c.directive();
+        let filter = EnvFilter::builder()
+            .parse(directive.as_str())
+            .with_context(|| format!("日志过滤指令无效: {}", directive))?;
+        // 包一层 reload，以便运行时修改过滤指令
+        let (filter, handle) = reload::Layer::new(filter);
+        log_filters.register(c.name(), directive, handle);
+        let (writer, guard, ansi) = match c.kind {
+            TargetKind::Stdout => {
                 let (stdout_nonblocking, stdout_guard) =
                     tracing_appender::non_blocking(std::io::stdout());
-                guard = stdout_guard;
-                layer = tracing_subscriber::fmt::layer()
-                    .event_format(format.clone().compact())
-                    .with_writer(stdout_nonblocking)
-                    .with_timer(ChronoLocal::new(TIME_FORMAT.to_string()))
-                    .with_ansi(true)
-                    .with_filter(level_filter);
+                (stdout_nonblocking, stdout_guard, true)
             }
-            configuration::logging::TargetKind::File => {
+            TargetKind::File => {
                 let file_appender = RollingFileAppender::new(
-                    c.rotation,
+                    c.rotation.clone(),
                     config.log_dir.as_str(),
                     c.filename.as_str(),
                 );
+                // 在创建 appender 之后启动，保证当前文件已存在，不会被当作旧文件处理
+                if c.has_retention() {
+                    spawn_retention(config.log_dir.clone(), c.clone());
+                }
                 let (file_nonblocking, file_guard) = tracing_appender::non_blocking(file_appender);
-                guard = file_guard;
-                layer = tracing_subscriber::fmt::layer()
-                    .event_format(format.clone().compact())
-                    .with_timer(ChronoLocal::new(TIME_FORMAT.to_string()))
-                    .with_writer(file_nonblocking)
-                    .with_ansi(false)
-                    .with_filter(level_filter);
+                (file_nonblocking, file_guard, false)
             }
-        }
-        layers.push(layer);
+        };
+        layers.push(create_layer(c, writer, ansi, filter));
         guards.push(guard);
     }
 
-    (layers, guards)
+    Ok((layers, guards))
+}
+
+/// 定期执行文件输出的日志保留策略
+fn spawn_retention(log_dir: String, target: Target) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(retention::RETENTION_INTERVAL);
+        loop {
+            interval.tick().await;
+            let (log_dir, target) = (log_dir.clone(), target.clone());
+            let result =
+                tokio::task::spawn_blocking(move || retention::enforce(log_dir.as_ref(), &target))
+                    .await;
+            match result {
+                Ok(Ok(())) => {}
+                // 日志目录尚未创建时无需处理
+                Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+                // 在全局 subscriber 安装之后运行，可以直接写日志
+                Ok(Err(e)) => warn!("清理日志文件失败: {}", e),
+                Err(e) => warn!("清理日志文件失败: {}", e),
+            }
+        }
+    });
 }
 
-fn create_format() -> Format<Json, impl FormatTime + Clone> {
-    tracing_subscriber::fmt::format()
+/// 按输出目标配置的格式创建日志层
+fn create_layer(
+    target: &Target,
+    writer: NonBlocking,
+    ansi: bool,
+    filter: reload::Layer<EnvFilter, Registry>,
+) -> BoxedLayer {
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
         .with_file(true)
         .with_line_number(true)
-        .with_timer(ChronoLocal::rfc_3339())
-        .with_source_location(true)
-        .with_target(false)
-        .json()
+        .with_target(false);
+    match target.format {
+        LogFormat::Json => layer
+            .json()
+            .with_timer(ChronoLocal::rfc_3339())
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_filter(filter)
+            .boxed(),
+        LogFormat::Compact => layer
+            .compact()
+            .with_timer(ChronoLocal::new(TIME_FORMAT.to_string()))
+            .with_ansi(ansi)
+            .with_filter(filter)
+            .boxed(),
+        LogFormat::Pretty => layer
+            .pretty()
+            .with_timer(ChronoLocal::new(TIME_FORMAT.to_string()))
+            .with_ansi(ansi)
+            .with_filter(filter)
+            .boxed(),
+    }
+}
+
+/// 创建通过 OTLP 导出 span 的日志层
+///
+/// 批量导出在 tokio 运行时中执行，需要在运行时内调用
+fn create_otlp_layer(settings: &OtlpSettings) -> anyhow::Result<(BoxedLayer, SdkTracerProvider)> {
+    let exporter = match settings.protocol {
+        OtlpProtocol::Grpc => SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(settings.endpoint.as_str())
+            .build(),
+        OtlpProtocol::Http => SpanExporter::builder()
+            .with_http()
+            .with_endpoint(settings.endpoint.as_str())
+            .build(),
+    }
+    .context("创建 OTLP 导出器失败")?;
+
+    let resource = Resource::builder()
+        .with_service_name(settings.service_name.clone())
+        .with_attribute(KeyValue::new(
+            "service.version",
+            settings.service_version.clone(),
+        ))
+        .build();
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+        settings.sampling_ratio,
+    )));
+    let provider = SdkTracerProvider::builder()
+        .with_span_processor(BatchSpanProcessor::builder(exporter, runtime::Tokio).build())
+        .with_sampler(sampler)
+        .with_resource(resource)
+        .build();
+
+    let filter = EnvFilter::builder()
+        .parse(settings.filter.as_str())
+        .with_context(|| format!("链路追踪过滤指令无效: {}", settings.filter))?;
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer(settings.service_name.clone()))
+        .with_filter(filter)
+        .boxed();
+
+    Ok((layer, provider))
 }
@@ -0,0 +1,254 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+use tracing::info;
+use tracing_subscriber::{EnvFilter, Registry, reload::Handle};
+
+/// 日志过滤指令修改错误
+#[derive(Error, Debug)]
+pub enum LogFilterError {
+    #[error("日志输出目标不存在: {0}")]
+    UnknownTarget(String),
+
+    #[error("日志过滤指令无效: {0}")]
+    InvalidDirective(String),
+
+    #[error("更新日志过滤指令失败: {0}")]
+    Reload(String),
+}
+
+/// 各日志输出目标的过滤指令，支持运行时修改
+#[derive(Clone, Default)]
+pub struct LogFilters {
+    inner: Arc<Mutex<Vec<TargetFilter>>>,
+}
+
+struct TargetFilter {
+    target: String,
+    default_directive: String,
+    directive: String,
+    handle: Handle<EnvFilter, Registry>,
+    /// 每次修改递增，自动恢复时用来判断是否已被再次修改
+    generation: u64,
+    revert_at: Option<DateTime<Utc>>,
+}
+
+/// 日志输出目标当前的过滤指令
+#[derive(Debug, Serialize)]
+pub struct LogFilterInfo {
+    pub target: String,
+    pub directive: String,
+    pub default_directive: String,
+    /// 自动恢复为默认指令的时间，秒级时间戳
+    pub revert_at: Option<i64>,
+}
+
+impl TargetFilter {
+    fn info(&self) -> LogFilterInfo {
+        LogFilterInfo {
+            target: self.target.clone(),
+            directive: self.directive.clone(),
+            default_directive: self.default_directive.clone(),
+            revert_at: self.revert_at.map(|t| t.timestamp()),
+        }
+    }
+}
+
+impl LogFilters {
+    pub(crate) fn register(
+        &self,
+        target: String,
+        directive: String,
+        handle: Handle<EnvFilter, Registry>,
+    ) {
+        self.inner.lock().unwrap().push(TargetFilter {
+            target,
+            default_directive: directive.clone(),
+            directive,
+            handle,
+            generation: 0,
+            revert_at: None,
+        });
+    }
+
+    pub fn list(&self) -> Vec<LogFilterInfo> {
+        self.inner
+            .lock()
+            .unwrap()
+            .iter()
+            .map(TargetFilter::info)
+            .collect()
+    }
+
+    /// 修改输出目标的过滤指令，`revert_after` 到期后自动恢复为默认指令
+    pub fn set(
+        &self,
+        target: &str,
+        directive: &str,
+        revert_after: Option<Duration>,
+    ) -> Result<LogFilterInfo, LogFilterError> {
+        let generation = {
+            let mut filters = self.inner.lock().unwrap();
+            let filter = find(&mut filters, target)?;
+            apply(filter, directive)?;
+            filter.revert_at = revert_after
+                .and_then(|d| chrono::Duration::from_std(d).ok())
+                .map(|d| Utc::now() + d);
+            filter.generation
+        };
+
+        if let Some(revert_after) = revert_after {
+            let filters = self.clone();
+            let target = target.to_string();
+            tokio::spawn(async move {
+                tokio::time::sleep(revert_after).await;
+                filters.revert(&target, generation);
+            });
+        }
+
+        self.get(target)
+    }
+
+    /// 恢复输出目标的默认过滤指令
+    pub fn reset(&self, target: &str) -> Result<LogFilterInfo, LogFilterError> {
+        let mut filters = self.inner.lock().unwrap();
+        let filter = find(&mut filters, target)?;
+        let directive = filter.default_directive.clone();
+        apply(filter, &directive)?;
+        filter.revert_at = None;
+        Ok(filter.info())
+    }
+
+    /// 修改输出目标的默认过滤指令
+    ///
+    /// 当前指令已被修改时只更新默认值，临时修改到期后恢复为新的默认指令
+    pub fn set_default(&self, target: &str, directive: &str) -> Result<(), LogFilterError> {
+        let mut filters = self.inner.lock().unwrap();
+        let filter = find(&mut filters, target)?;
+        if filter.directive == filter.default_directive {
+            apply(filter, directive)?;
+        }
+        filter.default_directive = directive.to_string();
+        Ok(())
+    }
+
+    fn get(&self, target: &str) -> Result<LogFilterInfo, LogFilterError> {
+        let mut filters = self.inner.lock().unwrap();
+        Ok(find(&mut filters, target)?.info())
+    }
+
+    /// 到期自动恢复，期间被再次修改过则不处理
+    fn revert(&self, target: &str, generation: u64) {
+        let mut filters = self.inner.lock().unwrap();
+        let Ok(filter) = find(&mut filters, target) else {
+            return;
+        };
+        if filter.generation != generation {
+            return;
+        }
+        let directive = filter.default_directive.clone();
+        if apply(filter, &directive).is_ok() {
+            filter.revert_at = None;
+            info!(target = %target, directive = %directive, "日志过滤指令已自动恢复");
+        }
+    }
+}
+
+fn find<'a>(
+    filters: &'a mut [TargetFilter],
+    target: &str,
+) -> Result<&'a mut TargetFilter, LogFilterError> {
+    filters
+        .iter_mut()
+        .find(|f| f.target == target)
+        .ok_or_else(|| LogFilterError::UnknownTarget(target.to_string()))
+}
+
+fn apply(filter: &mut TargetFilter, directive: &str) -> Result<(), LogFilterError> {
+    let env_filter = EnvFilter::builder()
+        .parse(directive)
+        .map_err(|e| LogFilterError::InvalidDirective(format!("{}: {}", directive, e)))?;
+    filter
+        .handle
+        .reload(env_filter)
+        .map_err(|e| LogFilterError::Reload(e.to_string()))?;
+    filter.directive = directive.to_string();
+    filter.generation += 1;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::reload;
+
+    use super::*;
+
+    /// 返回的 layer 需要保持存活，否则 handle 无法更新过滤指令
+    fn filters() -> (LogFilters, reload::Layer<EnvFilter, Registry>) {
+        let (layer, handle) = reload::Layer::new(EnvFilter::new("info"));
+        let filters = LogFilters::default();
+        filters.register("stdout".to_string(), "info".to_string(), handle);
+        (filters, layer)
+    }
+
+    #[tokio::test]
+    async fn set_and_reset_directive() {
+        let (filters, _layer) = filters();
+
+        let info = filters.set("stdout", "debug", None).unwrap();
+        assert_eq!(info.directive, "debug");
+        assert_eq!(info.default_directive, "info");
+        assert!(info.revert_at.is_none());
+
+        let info = filters.reset("stdout").unwrap();
+        assert_eq!(info.directive, "info");
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_target_and_invalid_directive() {
+        let (filters, _layer) = filters();
+
+        assert!(matches!(
+            filters.set("missing", "debug", None),
+            Err(LogFilterError::UnknownTarget(_))
+        ));
+        assert!(matches!(
+            filters.set("stdout", "info,[", None),
+            Err(LogFilterError::InvalidDirective(_))
+        ));
+        assert_eq!(filters.list()[0].directive, "info");
+    }
+
+    #[tokio::test]
+    async fn reverts_to_default_after_timeout() {
+        let (filters, _layer) = filters();
+
+        let info = filters
+            .set("stdout", "debug", Some(Duration::from_millis(50)))
+            .unwrap();
+        assert!(info.revert_at.is_some());
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let info = &filters.list()[0];
+        assert_eq!(info.directive, "info");
+        assert!(info.revert_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn later_change_cancels_pending_revert() {
+        let (filters, _layer) = filters();
+
+        filters
+            .set("stdout", "debug", Some(Duration::from_millis(50)))
+            .unwrap();
+        filters.set("stdout", "trace", None).unwrap();
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(filters.list()[0].directive, "trace");
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use flate2::{Compression, write::GzEncoder};
+
+use crate::configuration::logging::Target;
+
+/// 日志保留策略的检查间隔
+pub const RETENTION_INTERVAL: Duration = Duration::from_secs(60);
+
+const GZIP_SUFFIX: &str = ".gz";
+
+/// 按保留天数、文件数量、总大小清理日志目录，并压缩已轮转的日志文件
+///
+/// 当前正在写入的文件（文件名最新的未压缩文件）不会被压缩或删除
+pub fn enforce(log_dir: &Path, target: &Target) -> io::Result<()> {
+    let prefix = target.filename.as_str();
+    let mut files = list_log_files(log_dir, prefix)?;
+    let Some(active) = files
+        .iter()
+        .filter(|f| !is_compressed(f))
+        .max_by(|a, b| a.file_name().cmp(&b.file_name()))
+        .cloned()
+    else {
+        return Ok(());
+    };
+
+    if target.compress {
+        for file in files.iter_mut() {
+            if *file != active && !is_compressed(file) {
+                *file = compress(file)?;
+            }
+        }
+    }
+
+    if let Some(days) = target.max_age_days {
+        let cutoff = SystemTime::now()
+            .checked_sub(Duration::from_secs(days.saturating_mul(24 * 60 * 60)))
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let mut kept = Vec::with_capacity(files.len());
+        for file in files {
+            if file != active && fs::metadata(&file)?.modified()? < cutoff {
+                fs::remove_file(&file)?;
+            } else {
+                kept.push(file);
+            }
+        }
+        files = kept;
+    }
+
+    // 轮转文件名带有日期，按文件名排序即按时间排序
+    files.sort_by_key(|f| rotated_name(f));
+    let mut sizes = files
+        .iter()
+        .map(|f| fs::metadata(f).map(|m| m.len()))
+        .collect::<io::Result<Vec<u64>>>()?;
+    let mut total: u64 = sizes.iter().sum();
+    let max_total = target.max_total_size_mb.map(|mb| mb * 1024 * 1024);
+
+    while let Some(oldest) = files.first() {
+        let over_count = target.max_files.is_some_and(|max| files.len() > max);
+        let over_size = max_total.is_some_and(|max| total > max);
+        if (!over_count && !over_size) || *oldest == active {
+            break;
+        }
+        fs::remove_file(oldest)?;
+        total -= sizes.remove(0);
+        files.remove(0);
+    }
+
+    Ok(())
+}
+
+fn list_log_files(log_dir: &Path, prefix: &str) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(log_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        if name == prefix || name.starts_with(&format!("{}.", prefix)) {
+            files.push(entry.path());
+        }
+    }
+    Ok(files)
+}
+
+fn is_compressed(path: &Path) -> bool {
+    path.to_string_lossy().ends_with(GZIP_SUFFIX)
+}
+
+/// 去掉压缩后缀的文件名，保证压缩前后排序一致
+fn rotated_name(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    name.strip_suffix(GZIP_SUFFIX)
+        .map(str::to_string)
+        .unwrap_or(name)
+}
+
+/// gzip 压缩文件并删除原文件，返回压缩后的路径
+///
+/// 压缩文件沿用原文件的修改时间，以便按保留天数清理
+fn compress(path: &Path) -> io::Result<PathBuf> {
+    let target = PathBuf::from(format!("{}{}", path.display(), GZIP_SUFFIX));
+    let modified = fs::metadata(path)?.modified()?;
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut encoder = GzEncoder::new(
+        BufWriter::new(File::create(&target)?),
+        Compression::default(),
+    );
+    io::copy(&mut reader, &mut encoder)?;
+    let file = encoder.finish()?.into_inner().map_err(|e| e.into_error())?;
+    file.set_modified(modified)?;
+    fs::remove_file(path)?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// 在临时目录中创建 app.log.2026-01-01 起按天轮转的日志文件
+    struct LogDir {
+        path: PathBuf,
+    }
+
+    impl LogDir {
+        fn with_days(days: usize) -> Self {
+            let path = std::env::temp_dir().join(format!("retention-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&path).unwrap();
+            for day in 1..=days {
+                fs::write(
+                    path.join(format!("app.log.2026-01-{:02}", day)),
+                    "log line\n",
+                )
+                .unwrap();
+            }
+            Self { path }
+        }
+
+        fn age(&self, name: &str, age: Duration) {
+            File::options()
+                .write(true)
+                .open(self.path.join(name))
+                .unwrap()
+                .set_modified(SystemTime::now() - age)
+                .unwrap();
+        }
+
+        fn files(&self) -> Vec<String> {
+            let mut names: Vec<String> = fs::read_dir(&self.path)
+                .unwrap()
+                .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            names
+        }
+    }
+
+    impl Drop for LogDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
+    fn target(retention: serde_json::Value) -> Target {
+        let mut value = json!({"kind": "file", "filename": "app.log"});
+        value
+            .as_object_mut()
+            .unwrap()
+            .extend(retention.as_object().unwrap().clone());
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn keeps_newest_files_up_to_max_files() {
+        let dir = LogDir::with_days(4);
+
+        enforce(&dir.path, &target(json!({"max_files": 2}))).unwrap();
+
+        assert_eq!(dir.files(), ["app.log.2026-01-03", "app.log.2026-01-04"]);
+    }
+
+    #[test]
+    fn removes_files_older_than_max_age_but_not_the_active_file() {
+        let dir = LogDir::with_days(3);
+        dir.age("app.log.2026-01-01", DAY * 40);
+        dir.age("app.log.2026-01-02", DAY * 10);
+        dir.age("app.log.2026-01-03", DAY * 40);
+
+        enforce(&dir.path, &target(json!({"max_age_days": 30}))).unwrap();
+
+        assert_eq!(dir.files(), ["app.log.2026-01-02", "app.log.2026-01-03"]);
+    }
+
+    #[test]
+    fn compressed_files_keep_their_age() {
+        let dir = LogDir::with_days(2);
+        dir.age("app.log.2026-01-01", DAY * 40);
+
+        let compress = target(json!({"compress": true}));
+        enforce(&dir.path, &compress).unwrap();
+        assert_eq!(dir.files(), ["app.log.2026-01-01.gz", "app.log.2026-01-02"]);
+
+        let expire = target(json!({"compress": true, "max_age_days": 30}));
+        enforce(&dir.path, &expire).unwrap();
+        assert_eq!(dir.files(), ["app.log.2026-01-02"]);
+    }
+}
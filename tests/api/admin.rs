@@ -0,0 +1,279 @@
+use std::time::Duration;
+
+use kuai_saver::{
+    api_key::API_KEY_HEADER,
+    configuration::{
+        Settings,
+        application::{AdminRole, AdminSettings, OperatorSettings},
+    },
+};
+use reqwest::StatusCode;
+use secrecy::SecretString;
+use serde_json::{Value, json};
+
+use crate::{
+    helpers::{TestApp, spawn_app_with, spawn_app_with_db},
+    mock_pdd::{
+        API_GEN_SHORT_URL, API_GOODS_SEARCH, API_ORDER_DETAIL, goods_search_response,
+        goods_zs_unit_generate_response, order_detail_response,
+    },
+};
+
+const ADMIN_TOKEN: &str = "admin-token";
+const VIEWER_TOKEN: &str = "viewer-token";
+const OPERATOR_TOKEN: &str = "operator-token";
+const GOODS_URL: &str = "https://mobile.yangkeduo.com/goods.html?goods_id=123";
+const PARTNER_GOODS_URL: &str = "https://mobile.yangkeduo.com/goods.html?goods_id=456";
+
+fn configure_operators(settings: &mut Settings) {
+    let operator = |name: &str, token: &str, role| OperatorSettings {
+        name: name.to_string(),
+        token: SecretString::from(token),
+        role,
+    };
+    settings.application.admin = Some(AdminSettings {
+        token: SecretString::from(ADMIN_TOKEN),
+        operators: vec![
+            operator("viewer", VIEWER_TOKEN, AdminRole::Viewer),
+            operator("operator", OPERATOR_TOKEN, AdminRole::Operator),
+        ],
+    });
+}
+
+async fn spawn_app_with_operators() -> TestApp {
+    spawn_app_with(configure_operators).await
+}
+
+impl TestApp {
+    async fn admin_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        token: Option<&str>,
+    ) -> reqwest::Response {
+        let mut request = self
+            .client
+            .request(method, format!("{}/admin{}", self.address, path));
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+}
+
+#[tokio::test]
+async fn admin_api_requires_a_known_token() {
+    let app = spawn_app_with_operators().await;
+
+    for token in [None, Some("wrong-token")] {
+        let response = app
+            .admin_request(reqwest::Method::GET, "/upstream", token)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[tokio::test]
+async fn admin_api_is_disabled_without_admin_settings() {
+    let app = spawn_app_with(|settings| settings.application.admin = None).await;
+
+    let response = app
+        .admin_request(reqwest::Method::GET, "/log/filters", Some(ADMIN_TOKEN))
+        .await;
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn admin_token_must_use_the_bearer_scheme() {
+    let app = spawn_app_with_operators().await;
+
+    let response = app
+        .client
+        .get(format!("{}/admin/log/filters", app.address))
+        .header("Authorization", format!("Token {}", ADMIN_TOKEN))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = app
+        .admin_request(reqwest::Method::GET, "/log/filters", Some(ADMIN_TOKEN))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn viewers_can_read_but_not_modify() {
+    let app = spawn_app_with_operators().await;
+
+    let response = app
+        .admin_request(
+            reqwest::Method::GET,
+            "/upstream?page=1&page_size=5",
+            Some(VIEWER_TOKEN),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["page"], 1);
+    assert_eq!(body["page_size"], 5);
+    assert!(body["items"].is_array());
+
+    let response = app
+        .admin_request(
+            reqwest::Method::POST,
+            "/orders/pdd/200101-1/sync",
+            Some(VIEWER_TOKEN),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app
+        .admin_request(
+            reqwest::Method::DELETE,
+            "/log/filters/stdout",
+            Some(OPERATOR_TOKEN),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(app.pdd.requests_for(API_ORDER_DETAIL).is_empty());
+}
+
+#[tokio::test]
+async fn operators_can_resync_orders_from_the_platform() {
+    let app = spawn_app_with_db(configure_operators).await;
+    app.pdd
+        .respond(API_ORDER_DETAIL, order_detail_response("200101-1", 2));
+
+    let response = app
+        .admin_request(
+            reqwest::Method::POST,
+            "/orders/pdd/200101-1/sync",
+            Some(OPERATOR_TOKEN),
+        )
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let order: Value = response.json().await.unwrap();
+    assert_eq!(order["platform"], "pdd");
+    assert_eq!(order["order_sn"], "200101-1");
+    assert_eq!(order["pid"], "test-pid");
+    assert_eq!(order["order_amount"], "9.00");
+    assert_eq!(order["status"], 2);
+    let requests = app.pdd.requests_for(API_ORDER_DETAIL);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].params["order_sn"], "200101-1");
+
+    let response = app
+        .admin_request(
+            reqwest::Method::GET,
+            "/audit?target=order:pdd:200101-1",
+            Some(ADMIN_TOKEN),
+        )
+        .await;
+    let audit: Value = response.json().await.unwrap();
+    assert_eq!(audit["total"], 1);
+    assert_eq!(audit["items"][0]["actor"], "operator");
+    assert_eq!(audit["items"][0]["action"], "order.resync");
+    assert!(audit["items"][0]["before"].is_null());
+    assert_eq!(audit["items"][0]["after"], order);
+}
+
+#[tokio::test]
+async fn links_are_listed_by_the_pid_of_the_partner_key() {
+    let app = spawn_app_with_db(configure_operators).await;
+    app.pdd.respond(
+        API_GOODS_SEARCH,
+        goods_search_response("sign-123", 1000, 100),
+    );
+    app.pdd.respond(
+        API_GEN_SHORT_URL,
+        goods_zs_unit_generate_response("https://p.pinduoduo.com/abc"),
+    );
+    let issued: Value = app
+        .client
+        .post(format!("{}/admin/api_keys", app.address))
+        .bearer_auth(ADMIN_TOKEN)
+        .json(&json!({
+            "name": "partner",
+            "owner": "partner",
+            "pid": "partner-pid",
+            "scopes": ["translate"],
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // 默认推广位转的链接不属于任何合作方
+    app.translate_link(GOODS_URL).await;
+    let response = app
+        .client
+        .get(format!("{}/translate_link", app.address))
+        .query(&[("url", PARTNER_GOODS_URL)])
+        .header(API_KEY_HEADER, issued["key"].as_str().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // 价格在后台记录
+    let mut links = Value::Null;
+    for _ in 0..50 {
+        links = app
+            .admin_request(
+                reqwest::Method::GET,
+                "/links?pid=partner-pid",
+                Some(VIEWER_TOKEN),
+            )
+            .await
+            .json()
+            .await
+            .unwrap();
+        if links["total"] == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(links["total"], 1);
+    assert_eq!(links["items"][0]["source_url"], PARTNER_GOODS_URL);
+    assert_eq!(links["items"][0]["goods_id"], "sign-123");
+    assert_eq!(links["items"][0]["price"], "9.00");
+}
+
+#[tokio::test]
+async fn admin_lists_reject_invalid_page_sizes() {
+    let app = spawn_app_with_operators().await;
+
+    let response = app
+        .admin_request(
+            reqwest::Method::GET,
+            "/upstream?page_size=1000",
+            Some(ADMIN_TOKEN),
+        )
+        .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn audit_log_is_only_visible_to_admins() {
+    let app = spawn_app_with_operators().await;
+
+    let response = app
+        .admin_request(reqwest::Method::GET, "/audit", Some(OPERATOR_TOKEN))
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // 参数在查询数据库之前校验
+    let response = app
+        .admin_request(
+            reqwest::Method::GET,
+            "/audit?actor=operator&since=99999999999999",
+            Some(ADMIN_TOKEN),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
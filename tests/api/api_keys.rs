@@ -0,0 +1,249 @@
+use std::time::Duration;
+
+use kuai_saver::{
+    api_key::{API_KEY_HEADER, RateLimiter, RejectionCounter, generate_key, hash_key},
+    configuration::application::{AdminRole, AdminSettings, OperatorSettings},
+};
+use reqwest::StatusCode;
+use secrecy::SecretString;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::{
+    helpers::{TestApp, spawn_app, spawn_app_with, spawn_app_with_db},
+    mock_pdd::{
+        API_GEN_SHORT_URL, API_GOODS_SEARCH, goods_search_response, goods_zs_unit_generate_response,
+    },
+};
+
+const GOODS_URL: &str = "https://mobile.yangkeduo.com/goods.html?goods_id=123";
+
+#[tokio::test]
+async fn translate_link_requires_a_key_when_configured() {
+    let app = spawn_app_with(|settings| settings.application.api_keys.required = true).await;
+
+    let response = app.translate_link(GOODS_URL).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // 格式不正确的密钥直接拒绝
+    let response = app
+        .client
+        .get(format!("{}/translate_link", app.address))
+        .query(&[("url", GOODS_URL)])
+        .header(API_KEY_HEADER, "not-a-key")
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn order_detail_alias_requires_a_key_when_configured() {
+    let app = spawn_app_with(|settings| settings.application.api_keys.required = true).await;
+
+    let response = app.get(&format!("/order_detail?url={}", GOODS_URL)).await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+impl TestApp {
+    async fn translate_link_with_key(&self, key: &str) -> reqwest::Response {
+        self.client
+            .get(format!("{}/translate_link", self.address))
+            .query(&[("url", GOODS_URL)])
+            .header(API_KEY_HEADER, key)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    async fn key_usage(&self, key: &str) -> Value {
+        self.client
+            .get(format!("{}/api_keys/usage", self.address))
+            .header(API_KEY_HEADER, key)
+            .send()
+            .await
+            .expect("Failed to execute request")
+            .json()
+            .await
+            .unwrap()
+    }
+
+    fn respond_to_translation(&self) {
+        self.pdd
+            .respond(API_GOODS_SEARCH, goods_search_response("sign-123", 1000, 0));
+        self.pdd.respond(
+            API_GEN_SHORT_URL,
+            goods_zs_unit_generate_response("https://p.pinduoduo.com/abc"),
+        );
+    }
+}
+
+#[tokio::test]
+async fn keys_without_the_translate_scope_are_forbidden() {
+    let app = spawn_app_with_db(|_| {}).await;
+    // 已不再支持的授权范围
+    let key = app.create_key(None, &["legacy"], 100).await;
+
+    let response = app.translate_link_with_key(&key).await;
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(app.pdd.requests().is_empty());
+}
+
+#[tokio::test]
+async fn keys_are_rejected_once_the_daily_quota_is_used_up() {
+    let app = spawn_app_with_db(|settings| {
+        settings.application.api_keys.rejection_flush_interval_secs = 1;
+    })
+    .await;
+    app.respond_to_translation();
+    let key = app.create_key(None, &["translate"], 2).await;
+
+    for _ in 0..2 {
+        let response = app.translate_link_with_key(&key).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = app.translate_link_with_key(&key).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // 拒绝次数由后台任务定期写入
+    let mut usage = Value::Null;
+    for _ in 0..30 {
+        usage = app.key_usage(&key).await;
+        if usage["usage"][0]["rejected"] == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(usage["remaining_today"], 0);
+    assert_eq!(usage["usage"][0]["requests"], 2);
+    assert_eq!(usage["usage"][0]["rejected"], 1);
+}
+
+#[tokio::test]
+async fn the_key_pid_is_sent_upstream() {
+    let app = spawn_app_with_db(|_| {}).await;
+    app.respond_to_translation();
+    let key = app
+        .create_key(Some("partner-pid"), &["translate"], 100)
+        .await;
+
+    let response = app.translate_link_with_key(&key).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let searches = app.pdd.requests_for(API_GOODS_SEARCH);
+    assert_eq!(searches[0].params["pid"], "partner-pid");
+}
+
+#[tokio::test]
+async fn key_usage_requires_a_key() {
+    let app = spawn_app().await;
+
+    let response = app
+        .client
+        .get(format!("{}/api_keys/usage", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn only_admins_can_issue_keys() {
+    let app = spawn_app_with(|settings| {
+        settings.application.admin = Some(AdminSettings {
+            token: SecretString::from("admin-token"),
+            operators: vec![OperatorSettings {
+                name: "operator".to_string(),
+                token: SecretString::from("operator-token"),
+                role: AdminRole::Operator,
+            }],
+        });
+    })
+    .await;
+    let issue = |token: &'static str, body| {
+        app.client
+            .post(format!("{}/admin/api_keys", app.address))
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+    };
+
+    let body = json!({"name": "partner", "owner": "partner", "scopes": ["translate"]});
+    let response = issue("operator-token", body).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let body = json!({"name": "partner", "owner": "partner", "scopes": []});
+    let response = issue("admin-token", body).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn issued_keys_store_each_scope_once() {
+    let app = spawn_app_with_db(|settings| {
+        settings.application.admin = Some(AdminSettings {
+            token: SecretString::from("admin-token"),
+            operators: Vec::new(),
+        });
+    })
+    .await;
+
+    let response = app
+        .client
+        .post(format!("{}/admin/api_keys", app.address))
+        .bearer_auth("admin-token")
+        .json(&json!({
+            "name": "partner",
+            "owner": "partner",
+            "scopes": ["translate", "subscribe", "translate"]
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["scopes"], json!(["subscribe", "translate"]));
+}
+
+#[test]
+fn generated_keys_are_stored_as_hashes() {
+    let generated = generate_key();
+
+    assert!(generated.key.starts_with("ks_"));
+    assert!(generated.key.starts_with(&generated.prefix));
+    assert_eq!(generated.hash, hash_key(&generated.key));
+    assert!(!generated.hash.contains(&generated.key));
+    assert_ne!(generated.key, generate_key().key);
+}
+
+#[test]
+fn rate_limiter_allows_a_minute_of_burst_per_key() {
+    let limiter = RateLimiter::default();
+    let (key, other) = (Uuid::new_v4(), Uuid::new_v4());
+
+    for _ in 0..3 {
+        assert!(limiter.try_acquire(key, 3));
+    }
+    assert!(!limiter.try_acquire(key, 3));
+    assert!(limiter.try_acquire(other, 3));
+}
+
+#[test]
+fn rejection_counter_hands_out_counts_once() {
+    let counter = RejectionCounter::default();
+    let key = Uuid::new_v4();
+    let day = chrono::Utc::now().date_naive();
+
+    counter.record(key, day);
+    counter.record(key, day);
+    assert_eq!(counter.take()[&(key, day)], 2);
+    assert!(counter.take().is_empty());
+
+    // 写入失败放回后与新的次数合并
+    counter.restore(key, day, 2);
+    counter.record(key, day);
+    assert_eq!(counter.take()[&(key, day)], 3);
+}
@@ -0,0 +1,124 @@
+use kuai_saver::configuration::application::AdminSettings;
+use reqwest::StatusCode;
+use sea_orm::{ConnectionTrait, Statement};
+use secrecy::SecretString;
+use serde_json::json;
+
+use crate::{
+    helpers::{TestApp, spawn_app_with_db},
+    mock_pdd::{API_ORDER_DETAIL, order_detail_response},
+};
+
+const ADMIN_TOKEN: &str = "admin-token";
+
+async fn spawn_app_with_admin() -> TestApp {
+    spawn_app_with_db(|settings| {
+        settings.application.admin = Some(AdminSettings {
+            token: SecretString::from(ADMIN_TOKEN),
+            operators: Vec::new(),
+        });
+    })
+    .await
+}
+
+impl TestApp {
+    async fn count(&self, table: &str) -> i64 {
+        self.db
+            .query_one(Statement::from_string(
+                self.db.get_database_backend(),
+                format!("SELECT count(*) AS count FROM {}", table),
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .try_get("", "count")
+            .unwrap()
+    }
+
+    /// 让之后的审计记录写入失败
+    async fn break_audit_log(&self) {
+        self.db
+            .execute_unprepared(
+                "CREATE FUNCTION reject_insert() RETURNS trigger LANGUAGE plpgsql AS $$
+                BEGIN
+                    RAISE EXCEPTION 'audit_log unavailable';
+                END;
+                $$;
+                CREATE TRIGGER audit_log_broken BEFORE INSERT ON audit_log
+                    FOR EACH ROW EXECUTE FUNCTION reject_insert();",
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn issue_key(&self) -> reqwest::Response {
+        self.client
+            .post(format!("{}/admin/api_keys", self.address))
+            .bearer_auth(ADMIN_TOKEN)
+            .json(&json!({"name": "partner", "owner": "partner", "scopes": ["translate"]}))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    async fn resync_order(&self) -> reqwest::Response {
+        self.client
+            .post(format!("{}/admin/orders/pdd/200101-1/sync", self.address))
+            .bearer_auth(ADMIN_TOKEN)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+}
+
+#[tokio::test]
+async fn audit_log_rejects_updates_deletes_and_truncates() {
+    let app = spawn_app_with_admin().await;
+    assert_eq!(app.issue_key().await.status(), StatusCode::CREATED);
+
+    for sql in [
+        "UPDATE audit_log SET actor = 'someone else'",
+        "DELETE FROM audit_log",
+        "TRUNCATE audit_log",
+    ] {
+        let error = app.db.execute_unprepared(sql).await.unwrap_err();
+        assert!(
+            error.to_string().contains("只允许追加"),
+            "{}: {}",
+            sql,
+            error
+        );
+    }
+    assert_eq!(app.count("audit_log").await, 1);
+}
+
+#[tokio::test]
+async fn issuing_a_key_writes_the_audit_entry_in_the_same_transaction() {
+    let app = spawn_app_with_admin().await;
+
+    assert_eq!(app.issue_key().await.status(), StatusCode::CREATED);
+    assert_eq!(app.count("api_keys").await, 1);
+    assert_eq!(app.count("audit_log").await, 1);
+
+    app.break_audit_log().await;
+    let response = app.issue_key().await;
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(app.count("api_keys").await, 1);
+    assert_eq!(app.count("audit_log").await, 1);
+}
+
+#[tokio::test]
+async fn resyncing_an_order_writes_the_audit_entry_in_the_same_transaction() {
+    let app = spawn_app_with_admin().await;
+    app.pdd
+        .respond(API_ORDER_DETAIL, order_detail_response("200101-1", 2));
+    app.break_audit_log().await;
+
+    let response = app.resync_order().await;
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(app.pdd.requests_for(API_ORDER_DETAIL).len(), 1);
+    assert_eq!(app.count("orders").await, 0);
+    assert_eq!(app.count("audit_log").await, 0);
+}
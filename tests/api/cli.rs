@@ -0,0 +1,79 @@
+use clap::Parser;
+use kuai_saver::{
+    cli::{Cli, Command, ConfigCommand, redact_secrets},
+    configuration::environment::Environment,
+};
+use serde_json::json;
+
+#[test]
+fn global_config_flags_apply_to_subcommands() {
+    let cli = Cli::try_parse_from([
+        "kuai_saver",
+        "config",
+        "print",
+        "--redacted",
+        "--config-dir",
+        "/etc/kuai_saver",
+        "--environment",
+        "production",
+    ])
+    .unwrap();
+
+    let options = cli.config.load_options().unwrap();
+    assert_eq!(options.config_dir.to_str(), Some("/etc/kuai_saver"));
+    assert_eq!(options.environment, Environment::Production);
+    assert!(matches!(
+        cli.command,
+        Some(Command::Config {
+            command: ConfigCommand::Print { redacted: true }
+        })
+    ));
+}
+
+#[test]
+fn redact_secrets_hides_only_configured_secrets() {
+    let mut config = json!({
+        "application": {
+            "port": 8000,
+            "pdd": { "client_id": "id", "client_secret": "secret", "domain": "https://example.com" }
+        },
+        "db": { "username": "postgres", "password": "password" }
+    });
+
+    redact_secrets(&mut config);
+
+    assert_eq!(config["application"]["pdd"]["client_secret"], "<redacted>");
+    assert_eq!(config["application"]["pdd"]["client_id"], "<redacted>");
+    assert_eq!(config["db"]["password"], "<redacted>");
+    assert_eq!(config["db"]["username"], "postgres");
+    assert_eq!(
+        config["application"]["pdd"]["domain"],
+        "https://example.com"
+    );
+    assert!(config["application"].get("admin").is_none());
+}
+
+#[test]
+fn redact_secrets_hides_operator_tokens() {
+    let mut config = json!({
+        "application": {
+            "admin": {
+                "token": "admin-token",
+                "operators": [
+                    { "name": "alice", "token": "alice-token", "role": "viewer" },
+                    { "name": "bob", "token": "bob-token", "role": "operator" }
+                ]
+            }
+        }
+    });
+
+    redact_secrets(&mut config);
+
+    let admin = &config["application"]["admin"];
+    assert_eq!(admin["token"], "<redacted>");
+    for operator in admin["operators"].as_array().unwrap() {
+        assert_eq!(operator["token"], "<redacted>");
+    }
+    assert_eq!(admin["operators"][0]["name"], "alice");
+    assert_eq!(admin["operators"][1]["role"], "operator");
+}
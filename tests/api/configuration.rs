@@ -0,0 +1,174 @@
+use kuai_saver::{
+    configuration::{
+        LOCAL_OVERRIDE_FILE, LoadOptions, Settings,
+        environment::{Environment, UnknownEnvironment},
+    },
+    reload::ConfigChanges,
+};
+use secrecy::SecretString;
+
+use crate::helpers::test_settings;
+
+#[test]
+fn valid_settings_pass_validation() {
+    let mut settings = test_settings("https://gw-api.pinduoduo.com/api/router");
+    settings.application.port = 8000;
+
+    settings.validate().expect("test settings should be valid");
+}
+
+#[test]
+fn validation_reports_every_problem_with_its_path() {
+    let mut settings = test_settings("not a url");
+    settings.application.pdd.client_secret = SecretString::from("  ");
+    settings.application.price_alert.batch_size = 0;
+    // 文件下面不能创建目录
+    settings.log.log_dir = "Cargo.toml/logs".to_string();
+
+    let error = settings.validate().unwrap_err();
+
+    let paths: Vec<_> = error.problems.iter().map(|p| p.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "application.port",
+            "application.pdd.client_secret",
+            "application.pdd.domain",
+            "application.price_alert.batch_size",
+            "log.log_dir",
+        ]
+    );
+    assert!(error.to_string().contains("共 5 个问题"));
+}
+
+#[test]
+fn day_counts_are_bounded() {
+    let mut settings = test_settings("https://gw-api.pinduoduo.com/api/router");
+    settings.application.port = 8000;
+    settings.application.price_history.track_days = i64::MAX;
+    settings.log.targets[0].max_age_days = Some(u64::MAX);
+
+    let error = settings.validate().unwrap_err();
+
+    let paths: Vec<_> = error.problems.iter().map(|p| p.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "application.price_history.track_days",
+            "log.targets[0].max_age_days",
+        ]
+    );
+    assert!(error.to_string().contains("不能超过 3650 天"));
+}
+
+fn yaml(content: &str) -> config::Config {
+    config::Config::builder()
+        .add_source(config::File::from_str(content, config::FileFormat::Yaml))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn config_changes_separate_reloadable_and_restart_required_keys() {
+    let old = yaml(
+        r#"
+application:
+  port: 8000
+  pdd:
+    client_secret: old
+log:
+  log_dir: logs
+  targets:
+    - kind: stdout
+      level: info
+"#,
+    );
+    let new = yaml(
+        r#"
+application:
+  port: 8001
+  pdd:
+    client_secret: new
+log:
+  log_dir: logs
+  targets:
+    - kind: stdout
+      level: debug
+    - kind: file
+"#,
+    );
+
+    let changes = ConfigChanges::between(&old, &new).unwrap();
+
+    assert_eq!(
+        changes.reloaded,
+        ["application.pdd.client_secret", "log.targets[0].level"]
+    );
+    assert_eq!(
+        changes.restart_required,
+        ["application.port", "log.targets[1].kind"]
+    );
+}
+
+#[test]
+fn layers_are_applied_in_order_and_report_overridden_keys() {
+    let dir = std::env::temp_dir().join(format!("kuai_saver-layers-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let write = |name: &str, content: &str| std::fs::write(dir.join(name), content).unwrap();
+    write("base.yaml", "application:\n  port: 8000\n  host: 0.0.0.0\n");
+    write("staging.yaml", "application:\n  port: 8001\n");
+    write(LOCAL_OVERRIDE_FILE, "application:\n  host: 127.0.0.1\n");
+    write("extra.yaml", "application:\n  port: 8002\n");
+
+    let options = LoadOptions {
+        config_dir: dir.clone(),
+        environment: Environment::Staging,
+        config_file: Some(dir.join("extra.yaml")),
+        // 不读取进程环境变量，运行测试时设置的 APP_* 不影响结果
+        env: Some(config::Map::from([(
+            "APP_APPLICATION__PORT".to_string(),
+            "8003".to_string(),
+        )])),
+    };
+    let (source, layers) = Settings::layered_source(&options).unwrap();
+
+    assert_eq!(source.get_int("application.port").unwrap(), 8003);
+    assert_eq!(source.get_string("application.host").unwrap(), "127.0.0.1");
+    let names: Vec<_> = layers.iter().map(|l| l.name.as_str()).collect();
+    assert!(names[0].ends_with("base.yaml"));
+    assert!(names[1].ends_with("staging.yaml"));
+    assert!(names[2].ends_with(LOCAL_OVERRIDE_FILE));
+    assert!(names[3].ends_with("extra.yaml"));
+    assert!(layers[0].overridden.is_empty());
+    assert_eq!(layers[1].overridden, ["application.port"]);
+    assert_eq!(layers[2].overridden, ["application.host"]);
+    assert_eq!(layers[3].overridden, ["application.port"]);
+    assert_eq!(layers[4].name, "环境变量 APP_*");
+    assert_eq!(layers[4].overridden, ["application.port"]);
+
+    // 本地覆盖文件是可选的，环境配置文件必须存在
+    std::fs::remove_file(dir.join(LOCAL_OVERRIDE_FILE)).unwrap();
+    let (_, layers) = Settings::layered_source(&options).unwrap();
+    assert_eq!(layers.len(), 4);
+    let missing = LoadOptions {
+        environment: Environment::Production,
+        ..options
+    };
+    assert!(Settings::layered_source(&missing).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unknown_environment_is_rejected() {
+    assert_eq!(
+        "staging".parse::<Environment>().unwrap(),
+        Environment::Staging
+    );
+
+    let error: UnknownEnvironment = "prod".parse::<Environment>().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "未知的运行环境 \"prod\"，可选值为 local、test、staging、production"
+    );
+}
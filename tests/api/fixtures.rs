@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use kuai_saver::configuration::application::{FixtureMode, FixtureSettings};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::{
+    helpers::{CLIENT_ID, CLIENT_SECRET, PID, TestApp, spawn_app_with},
+    mock_pdd::{
+        API_GEN_SHORT_URL, API_GOODS_SEARCH, goods_search_response, goods_zs_unit_generate_response,
+    },
+};
+
+const GOODS_URL: &str = "https://mobile.yangkeduo.com/goods.html?goods_id=456";
+
+async fn spawn_app_with_fixtures(mode: FixtureMode, dir: &Path) -> TestApp {
+    let dir = dir.to_str().unwrap().to_string();
+    spawn_app_with(|settings| {
+        settings.application.pdd.fixtures = Some(FixtureSettings { mode, dir });
+    })
+    .await
+}
+
+fn fixture_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("kuai_saver-fixtures-{}", uuid::Uuid::new_v4()))
+}
+
+#[tokio::test]
+async fn recorded_fixtures_are_redacted() {
+    let dir = fixture_dir();
+    let app = spawn_app_with_fixtures(FixtureMode::Record, &dir).await;
+    app.pdd
+        .respond(API_GOODS_SEARCH, goods_search_response("sign-456", 2000, 0));
+    app.pdd.respond(
+        API_GEN_SHORT_URL,
+        goods_zs_unit_generate_response("https://p.pinduoduo.com/def"),
+    );
+
+    assert_eq!(app.translate_link(GOODS_URL).await.status(), StatusCode::OK);
+
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 2);
+    for file in files {
+        let content = std::fs::read_to_string(&file).unwrap();
+        for secret in [CLIENT_ID, CLIENT_SECRET, PID] {
+            assert!(
+                !content.contains(secret),
+                "{} leaked into {}",
+                secret,
+                file.display()
+            );
+        }
+        let fixture: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(fixture["params"]["sign"], "<redacted>");
+        assert_eq!(fixture["params"]["timestamp"], "<redacted>");
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn replay_serves_recorded_responses_without_gateway() {
+    let dir = fixture_dir();
+    let recorder = spawn_app_with_fixtures(FixtureMode::Record, &dir).await;
+    recorder.pdd.respond(
+        API_GOODS_SEARCH,
+        goods_search_response("sign-456", 2000, 500),
+    );
+    recorder.pdd.respond(
+        API_GEN_SHORT_URL,
+        goods_zs_unit_generate_response("https://p.pinduoduo.com/def"),
+    );
+    let recorded: Value = recorder
+        .translate_link(GOODS_URL)
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // 回放时网关没有任何预设响应，请求网关必然失败
+    let replayer = spawn_app_with_fixtures(FixtureMode::Replay, &dir).await;
+    let response = replayer.translate_link(GOODS_URL).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let replayed: Value = response.json().await.unwrap();
+    assert_eq!(replayed, recorded);
+    assert!(replayer.pdd.requests().is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn replay_fails_for_unrecorded_requests() {
+    let dir = fixture_dir();
+    let app = spawn_app_with_fixtures(FixtureMode::Replay, &dir).await;
+
+    let response = app.translate_link(GOODS_URL).await;
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(app.pdd.requests().is_empty());
+}
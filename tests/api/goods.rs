@@ -0,0 +1,40 @@
+use reqwest::StatusCode;
+
+use crate::{
+    helpers::{PID, spawn_app},
+    mock_pdd::{API_GOODS_SEARCH, API_TOP_GOODS},
+};
+
+#[tokio::test]
+async fn goods_history_rejects_out_of_range_limit() {
+    let app = spawn_app().await;
+
+    for limit in [0, 1001] {
+        let response = app
+            .get(&format!("/goods/pdd/goods-1/history?limit={}", limit))
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn feeds_ignore_caller_supplied_pid() {
+    let app = spawn_app().await;
+
+    app.get("/goods/top?pid=someone-elses-pid").await;
+
+    let requests = app.pdd.requests_for(API_TOP_GOODS);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].params["p_id"], PID);
+}
+
+#[tokio::test]
+async fn search_ignores_caller_supplied_pid() {
+    let app = spawn_app().await;
+
+    app.get("/goods/search?q=phone&pid=someone-elses-pid").await;
+
+    let requests = app.pdd.requests_for(API_GOODS_SEARCH);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].params["pid"], PID);
+}
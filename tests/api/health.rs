@@ -0,0 +1,54 @@
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::helpers::spawn_app;
+
+const GOODS_URL: &str = "https://mobile.yangkeduo.com/goods.html?goods_id=123";
+
+#[tokio::test]
+async fn liveness_does_not_depend_on_components() {
+    let app = spawn_app().await;
+
+    let response = app.get("/health/live").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "up");
+}
+
+#[tokio::test]
+async fn readiness_reports_each_component() {
+    // 测试配置的数据库不可达
+    let app = spawn_app().await;
+
+    let response = app.get("/health/ready").await;
+
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "down");
+    let components = body["components"].as_object().unwrap();
+    assert_eq!(components["database"]["status"], "down");
+    assert!(components["database"]["detail"].is_string());
+    assert_eq!(components["config"]["status"], "up");
+    assert_eq!(components["translator:pdd"]["status"], "up");
+    assert_eq!(components["translator:pdd"]["detail"], "熔断器状态: closed");
+    for (name, job) in components.iter().filter(|(k, _)| k.starts_with("job:")) {
+        assert_eq!(job["status"], "up", "{} 应该在运行", name);
+    }
+}
+
+#[tokio::test]
+async fn readiness_reports_tripped_circuit_breakers() {
+    let app = spawn_app().await;
+    app.pdd.fail_next(5, StatusCode::BAD_GATEWAY);
+    for _ in 0..5 {
+        app.translate_link(GOODS_URL).await;
+    }
+
+    let body: Value = app.get("/health/ready").await.json().await.unwrap();
+
+    // 熔断只在详情中报告，不会让实例被摘除
+    let translator = &body["components"]["translator:pdd"];
+    assert_eq!(translator["status"], "up");
+    assert_eq!(translator["detail"], "熔断器状态: open");
+}
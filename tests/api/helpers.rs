@@ -0,0 +1,162 @@
+use kuai_saver::{
+    api_key::generate_key,
+    configuration::{DatabaseSettings, Settings},
+    entity::api_keys::{self, NewApiKey},
+    migration,
+    startup::Application,
+    telemetry::LogFilters,
+};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::mock_pdd::MockPdd;
+
+pub const CLIENT_ID: &str = "test-client-id";
+pub const CLIENT_SECRET: &str = "test-client-secret";
+pub const PID: &str = "test-pid";
+/// 本地测试数据库的端口，依赖数据库的测试为每个用例创建独立的库
+pub const DB_PORT: u16 = 5432;
+
+/// 运行在随机端口上的应用，上游指向进程内的拼多多网关
+pub struct TestApp {
+    pub address: String,
+    pub client: reqwest::Client,
+    pub pdd: MockPdd,
+    /// 应用使用的数据库，未调用 `spawn_app_with_db` 时不可达
+    pub db: DatabaseConnection,
+    shutdown: CancellationToken,
+}
+
+impl TestApp {
+    pub async fn get(&self, path: &str) -> reqwest::Response {
+        self.client
+            .get(format!("{}{}", self.address, path))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    /// 直接在数据库中创建密钥，返回明文
+    pub async fn create_key(&self, pid: Option<&str>, scopes: &[&str], daily_quota: i64) -> String {
+        let generated = generate_key();
+        api_keys::create(
+            &self.db,
+            NewApiKey {
+                name: "partner".to_string(),
+                owner: "partner".to_string(),
+                pid: pid.map(str::to_string),
+                key_prefix: generated.prefix,
+                key_hash: generated.hash,
+                scopes: scopes.iter().map(|s| s.to_string()).collect(),
+                rate_limit_per_minute: 60,
+                daily_quota,
+            },
+        )
+        .await
+        .expect("Failed to create key");
+        generated.key
+    }
+
+    pub async fn translate_link(&self, url: &str) -> reqwest::Response {
+        self.client
+            .get(format!("{}/translate_link", self.address))
+            .query(&[("url", url)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        self.shutdown.cancel();
+    }
+}
+
+pub async fn spawn_app() -> TestApp {
+    spawn_app_with(|_| {}).await
+}
+
+/// 启动前可以修改测试配置
+pub async fn spawn_app_with(configure: impl FnOnce(&mut Settings)) -> TestApp {
+    let pdd = MockPdd::start(CLIENT_SECRET).await;
+    let mut settings = test_settings(&pdd.address);
+    configure(&mut settings);
+    spawn(pdd, settings).await
+}
+
+/// 使用独立的测试库启动应用，启动前可以修改测试配置
+pub async fn spawn_app_with_db(configure: impl FnOnce(&mut Settings)) -> TestApp {
+    let pdd = MockPdd::start(CLIENT_SECRET).await;
+    let mut settings = test_settings(&pdd.address);
+    configure_database(&mut settings.db).await;
+    configure(&mut settings);
+    spawn(pdd, settings).await
+}
+
+async fn spawn(pdd: MockPdd, settings: Settings) -> TestApp {
+    let db = Database::connect(settings.db.build())
+        .await
+        .expect("Failed to connect to database");
+    let app = Application::build(settings, LogFilters::default())
+        .await
+        .expect("Failed to build application");
+    let address = format!("http://127.0.0.1:{}", app.port());
+    let shutdown = app.shutdown_token();
+    tokio::spawn(app.run_until_stopped());
+
+    TestApp {
+        address,
+        client: reqwest::Client::new(),
+        pdd,
+        db,
+        shutdown,
+    }
+}
+
+/// 创建空的测试库，并让配置指向它
+pub async fn create_database(settings: &mut DatabaseSettings) -> DatabaseConnection {
+    settings.port = DB_PORT;
+    settings.database = format!("test_{}", Uuid::new_v4().simple());
+
+    let mut maintenance = settings.clone();
+    maintenance.database = "postgres".to_string();
+    Database::connect(maintenance.build())
+        .await
+        .expect("Failed to connect to Postgres")
+        .execute_unprepared(&format!(r#"CREATE DATABASE "{}""#, settings.database))
+        .await
+        .expect("Failed to create database");
+
+    Database::connect(settings.build())
+        .await
+        .expect("Failed to connect to database")
+}
+
+/// 创建测试库并执行所有表结构变更
+pub async fn configure_database(settings: &mut DatabaseSettings) -> DatabaseConnection {
+    let db = create_database(settings).await;
+    migration::run(&db)
+        .await
+        .expect("Failed to migrate database");
+    db
+}
+
+/// 基于 base.yaml 的测试配置，不读取环境变量和本地配置
+pub fn test_settings(pdd_domain: &str) -> Settings {
+    config::Config::builder()
+        .add_source(config::File::with_name("configuration/base.yaml"))
+        .set_override("application.host", "127.0.0.1")
+        .and_then(|b| b.set_override("application.port", 0))
+        .and_then(|b| b.set_override("application.pdd.domain", pdd_domain))
+        .and_then(|b| b.set_override("application.pdd.client_id", CLIENT_ID))
+        .and_then(|b| b.set_override("application.pdd.client_secret", CLIENT_SECRET))
+        .and_then(|b| b.set_override("application.pdd.pid", PID))
+        // 数据库不可达，依赖数据库的功能快速失败
+        .and_then(|b| b.set_override("db.port", 1))
+        .and_then(|b| b.set_override("db.password", "password"))
+        .and_then(|b| b.build())
+        .and_then(|c| c.try_deserialize())
+        .expect("Failed to load test configuration")
+}
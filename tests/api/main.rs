@@ -0,0 +1,16 @@
+mod admin;
+mod api_keys;
+mod audit;
+mod cli;
+mod configuration;
+mod fixtures;
+mod goods;
+mod health;
+mod helpers;
+mod migration;
+mod mock_pdd;
+mod order_sync;
+mod request_id;
+mod secrets;
+mod subscriptions;
+mod translate_link;
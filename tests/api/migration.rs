@@ -0,0 +1,51 @@
+use kuai_saver::{
+    configuration::DatabaseSettings,
+    migration::{self, MIGRATIONS},
+};
+use sea_orm::ConnectionTrait;
+
+use crate::helpers::{configure_database, create_database, test_settings};
+
+fn db_settings() -> DatabaseSettings {
+    test_settings("http://127.0.0.1:1").db
+}
+
+#[tokio::test]
+async fn migrate_applies_every_migration_once() {
+    let mut settings = db_settings();
+    let db = configure_database(&mut settings).await;
+
+    let executed = migration::run(&db).await.unwrap();
+
+    assert!(executed.is_empty());
+}
+
+#[tokio::test]
+async fn migrate_adopts_tables_created_before_the_runner() {
+    let mut settings = db_settings();
+    let db = create_database(&mut settings).await;
+    // 引入迁移前按 entity 中的建表语句手工建表，没有 schema_migrations
+    for m in MIGRATIONS.iter().filter(|m| m.version <= 4) {
+        db.execute_unprepared(m.sql).await.unwrap();
+    }
+
+    let executed = migration::run(&db).await.unwrap();
+
+    assert_eq!(executed.len(), MIGRATIONS.len());
+}
+
+#[tokio::test]
+async fn concurrent_migrate_runs_apply_each_migration_once() {
+    let mut settings = db_settings();
+    let db = create_database(&mut settings).await;
+
+    let (a, b, c, d) = tokio::join!(
+        migration::run(&db),
+        migration::run(&db),
+        migration::run(&db),
+        migration::run(&db)
+    );
+
+    let executed: usize = [a, b, c, d].into_iter().map(|r| r.unwrap().len()).sum();
+    assert_eq!(executed, MIGRATIONS.len());
+}
@@ -0,0 +1,227 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use kuai_saver::{middleware::REQUEST_ID_HEADER, util::generate_signature};
+use serde_json::{Value, json};
+
+pub const API_GOODS_SEARCH: &str = "pdd.ddk.goods.search";
+pub const API_GEN_SHORT_URL: &str = "pdd.ddk.goods.zs.unit.url.gen";
+pub const API_ORDER_DETAIL: &str = "pdd.ddk.order.detail.get";
+pub const API_ORDER_LIST: &str = "pdd.ddk.order.list.increment.get";
+pub const API_TOP_GOODS: &str = "pdd.ddk.top.goods.list.query";
+
+/// 进程内的拼多多开放平台网关
+///
+/// 按 `type` 参数返回预设的响应体，并像真实网关一样校验签名
+#[derive(Clone)]
+pub struct MockPdd {
+    pub address: String,
+    state: Arc<MockState>,
+}
+
+struct MockState {
+    client_secret: String,
+    responses: Mutex<HashMap<String, Value>>,
+    latency: Mutex<Duration>,
+    failures: Mutex<VecDeque<StatusCode>>,
+    requests: Mutex<Vec<ReceivedRequest>>,
+}
+
+/// 网关收到的请求
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    pub api_type: String,
+    pub params: HashMap<String, String>,
+    pub request_id: Option<String>,
+}
+
+impl MockPdd {
+    pub async fn start(client_secret: &str) -> Self {
+        let state = Arc::new(MockState {
+            client_secret: client_secret.to_string(),
+            responses: Mutex::new(HashMap::new()),
+            latency: Mutex::new(Duration::ZERO),
+            failures: Mutex::new(VecDeque::new()),
+            requests: Mutex::new(Vec::new()),
+        });
+        let router = Router::new()
+            .route("/api/router", get(handle))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind mock gateway");
+        let address = format!("http://{}/api/router", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        Self { address, state }
+    }
+
+    /// 设置某个接口返回的响应体
+    pub fn respond(&self, api_type: &str, body: Value) {
+        self.state
+            .responses
+            .lock()
+            .unwrap()
+            .insert(api_type.to_string(), body);
+    }
+
+    /// 每个请求返回前的延迟
+    pub fn set_latency(&self, latency: Duration) {
+        *self.state.latency.lock().unwrap() = latency;
+    }
+
+    /// 接下来的 `times` 个请求直接返回 `status`
+    pub fn fail_next(&self, times: usize, status: StatusCode) {
+        self.state
+            .failures
+            .lock()
+            .unwrap()
+            .extend(std::iter::repeat_n(status, times));
+    }
+
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    pub fn requests_for(&self, api_type: &str) -> Vec<ReceivedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|r| r.api_type == api_type)
+            .collect()
+    }
+}
+
+async fn handle(
+    State(state): State<Arc<MockState>>,
+    Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    let api_type = params.get("type").cloned().unwrap_or_default();
+    state.requests.lock().unwrap().push(ReceivedRequest {
+        api_type: api_type.clone(),
+        params: params.clone(),
+        request_id: headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+    });
+
+    let latency = *state.latency.lock().unwrap();
+    if !latency.is_zero() {
+        tokio::time::sleep(latency).await;
+    }
+
+    let failure = state.failures.lock().unwrap().pop_front();
+    if let Some(status) = failure {
+        return status.into_response();
+    }
+
+    if !signature_matches(&params, &state.client_secret) {
+        return Json(error_response(10019, "签名错误")).into_response();
+    }
+
+    let body = state.responses.lock().unwrap().get(&api_type).cloned();
+    match body {
+        Some(body) => Json(body).into_response(),
+        None => Json(error_response(10001, "未知的接口类型")).into_response(),
+    }
+}
+
+fn signature_matches(params: &HashMap<String, String>, client_secret: &str) -> bool {
+    let Some(sign) = params.get("sign") else {
+        return false;
+    };
+    let unsigned = params
+        .iter()
+        .filter(|(k, _)| k.as_str() != "sign")
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    generate_signature(unsigned, client_secret) == *sign
+}
+
+/// 商品搜索响应，金额单位为分，佣金比例为千分比
+pub fn goods_search_response(
+    goods_sign: &str,
+    min_group_price: i64,
+    coupon_discount: i64,
+) -> Value {
+    json!({
+        "goods_search_response": {
+            "goods_list": [{
+                "goods_sign": goods_sign,
+                "goods_name": "测试商品",
+                "goods_thumbnail_url": "https://img.example.com/thumb.jpg",
+                "goods_image_url": "https://img.example.com/main.jpg",
+                "mall_name": "测试店铺",
+                "sales_tip": "10万+",
+                "min_group_price": min_group_price,
+                "coupon_discount": coupon_discount,
+                "coupon_min_order_amount": 0,
+                "promotion_rate": 100
+            }],
+            "list_id": "list-1",
+            "total_count": 1
+        }
+    })
+}
+
+/// 多多进宝转链响应
+pub fn goods_zs_unit_generate_response(short_url: &str) -> Value {
+    json!({
+        "goods_zs_unit_generate_response": {
+            "short_url": short_url
+        }
+    })
+}
+
+/// 订单详情响应，金额单位为分
+pub fn order_detail_response(order_sn: &str, order_status: i32) -> Value {
+    json!({
+        "order_detail_response": {
+            "order_sn": order_sn,
+            "goods_sign": "sign-123",
+            "goods_name": "测试商品",
+            "p_id": "test-pid",
+            "order_amount": 900,
+            "promotion_rate": 100,
+            "promotion_amount": 90,
+            "order_status": order_status,
+            "order_create_time": 1700000000,
+            "order_modify_at": 1700003600
+        }
+    })
+}
+
+/// 增量订单列表响应，只有一个订单
+pub fn order_list_response(order_sn: &str, order_status: i32) -> Value {
+    let order = order_detail_response(order_sn, order_status)["order_detail_response"].clone();
+    json!({
+        "order_list_get_response": {
+            "order_list": [order],
+            "total_count": 1
+        }
+    })
+}
+
+/// 网关业务错误响应，HTTP 状态码仍为 200
+pub fn error_response(error_code: i64, error_msg: &str) -> Value {
+    json!({
+        "error_response": {
+            "error_code": error_code,
+            "error_msg": error_msg,
+            "sub_code": "",
+            "sub_msg": ""
+        }
+    })
+}
@@ -0,0 +1,27 @@
+use chrono::{Duration, TimeZone, Utc};
+use kuai_saver::job;
+
+use crate::{
+    helpers::{spawn_app_with_db, test_settings},
+    mock_pdd::{API_ORDER_LIST, order_list_response},
+};
+
+#[tokio::test]
+async fn order_sync_exports_how_far_it_has_synced() {
+    let app = spawn_app_with_db(|_| {}).await;
+    app.pdd
+        .respond(API_ORDER_LIST, order_list_response("200101-1", 1));
+    let settings = test_settings(&app.pdd.address).application;
+    let until = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+    let synced = job::sync_orders(&app.db, &settings, until - Duration::hours(1), until)
+        .await
+        .unwrap();
+
+    assert_eq!(synced, 1);
+    let metrics = app.get("/metrics").await.text().await.unwrap();
+    assert!(metrics.contains("# TYPE kuai_saver_order_sync_last_synced_timestamp_seconds gauge"));
+    assert!(metrics.contains(
+        r#"kuai_saver_order_sync_last_synced_timestamp_seconds{platform="pdd"} 1700000000"#
+    ));
+}
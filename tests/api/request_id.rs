@@ -0,0 +1,61 @@
+use kuai_saver::middleware::REQUEST_ID_HEADER;
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::helpers::spawn_app;
+
+#[tokio::test]
+async fn incoming_request_id_is_echoed() {
+    let app = spawn_app().await;
+
+    let response = app
+        .client
+        .get(format!("{}/ping", app.address))
+        .header(REQUEST_ID_HEADER, "req-123")
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-123");
+}
+
+#[tokio::test]
+async fn missing_or_invalid_request_id_is_replaced() {
+    let app = spawn_app().await;
+    let too_long = "a".repeat(200);
+
+    for incoming in [None, Some("has space"), Some(too_long.as_str())] {
+        let mut request = app.client.get(format!("{}/ping", app.address));
+        if let Some(id) = incoming {
+            request = request.header(REQUEST_ID_HEADER, id);
+        }
+        let response = request.send().await.expect("Failed to execute request");
+
+        let id = response.headers()[REQUEST_ID_HEADER].to_str().unwrap();
+        assert!(
+            uuid::Uuid::parse_str(id).is_ok(),
+            "{:?} 应该被替换",
+            incoming
+        );
+    }
+}
+
+#[tokio::test]
+async fn errors_are_json_with_the_request_id() {
+    let app = spawn_app().await;
+
+    let response = app
+        .client
+        .get(format!("{}/goods/search?q=%20", app.address))
+        .header(REQUEST_ID_HEADER, "req-456")
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-456");
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], 400);
+    assert_eq!(body["request_id"], "req-456");
+    assert!(body["message"].as_str().is_some_and(|m| !m.is_empty()));
+}
@@ -0,0 +1,203 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use kuai_saver::configuration::secrets::{EncryptedFileProvider, resolve};
+
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("kuai_saver-secrets-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    fn write(&self, name: &str, content: &str) -> String {
+        let path = self.0.join(name);
+        std::fs::write(&path, content).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn yaml(content: &str) -> config::Config {
+    config::Config::builder()
+        .add_source(config::File::from_str(content, config::FileFormat::Yaml))
+        .build()
+        .unwrap()
+}
+
+const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+#[test]
+fn secrets_are_read_from_files() {
+    let dir = TempDir::new();
+    let secret_file = dir.write("client_secret", "from-file\n");
+    let raw = yaml(&format!(
+        "application:\n  pdd:\n    client_id: inline\n    client_secret_file: {}\n",
+        secret_file
+    ));
+
+    let resolved = resolve(raw).unwrap();
+
+    assert_eq!(
+        resolved
+            .get_string("application.pdd.client_secret")
+            .unwrap(),
+        "from-file"
+    );
+    assert_eq!(
+        resolved.get_string("application.pdd.client_id").unwrap(),
+        "inline"
+    );
+}
+
+#[test]
+fn operator_tokens_are_read_from_files() {
+    let dir = TempDir::new();
+    let token_file = dir.write("bob_token", "bob-from-file\n");
+    let raw = yaml(&format!(
+        r#"
+application:
+  admin:
+    operators:
+      - name: alice
+        token: alice-inline
+        role: viewer
+      - name: bob
+        token_file: {}
+        role: operator
+"#,
+        token_file
+    ));
+
+    let resolved = resolve(raw).unwrap();
+
+    assert_eq!(
+        resolved
+            .get_string("application.admin.operators[0].token")
+            .unwrap(),
+        "alice-inline"
+    );
+    assert_eq!(
+        resolved
+            .get_string("application.admin.operators[1].token")
+            .unwrap(),
+        "bob-from-file"
+    );
+}
+
+#[test]
+fn missing_secret_file_is_an_error() {
+    let raw = yaml("db:\n  password_file: /nonexistent/db_password\n");
+
+    let error = resolve(raw).unwrap_err();
+
+    assert!(format!("{:#}", error).contains("db.password_file"));
+}
+
+#[test]
+fn encrypted_file_provides_unconfigured_secrets() {
+    let dir = TempDir::new();
+    let key_file = dir.write("secrets.key", KEY);
+    let secrets = BTreeMap::from([
+        (
+            "application.pdd.client_secret".to_string(),
+            "sealed-secret".to_string(),
+        ),
+        (
+            "application.pdd.client_id".to_string(),
+            "sealed-id".to_string(),
+        ),
+        (
+            "application.admin.token".to_string(),
+            "sealed-token".to_string(),
+        ),
+    ]);
+    let sealed = dir.write(
+        "secrets.enc",
+        &EncryptedFileProvider::seal(&key_file, &secrets).unwrap(),
+    );
+    let raw = yaml(&format!(
+        "secrets:\n  kind: encrypted_file\n  path: {}\n  key_file: {}\napplication:\n  pdd:\n    client_id: inline\n",
+        sealed, key_file
+    ));
+
+    let resolved = resolve(raw).unwrap();
+
+    assert_eq!(
+        resolved
+            .get_string("application.pdd.client_secret")
+            .unwrap(),
+        "sealed-secret"
+    );
+    // 明文配置优先
+    assert_eq!(
+        resolved.get_string("application.pdd.client_id").unwrap(),
+        "inline"
+    );
+    // 没有配置 admin 时不会因为加密文件中有令牌而启用管理接口
+    assert!(resolved.get_string("application.admin.token").is_err());
+}
+
+#[test]
+fn encrypted_file_provides_operator_tokens() {
+    let dir = TempDir::new();
+    let key_file = dir.write("secrets.key", KEY);
+    let secrets = BTreeMap::from([(
+        "application.admin.operators[0].token".to_string(),
+        "sealed-alice".to_string(),
+    )]);
+    let sealed = dir.write(
+        "secrets.enc",
+        &EncryptedFileProvider::seal(&key_file, &secrets).unwrap(),
+    );
+    let raw = yaml(&format!(
+        r#"
+secrets:
+  kind: encrypted_file
+  path: {}
+  key_file: {}
+application:
+  admin:
+    operators:
+      - name: alice
+        role: viewer
+"#,
+        sealed, key_file
+    ));
+
+    let resolved = resolve(raw).unwrap();
+
+    assert_eq!(
+        resolved
+            .get_string("application.admin.operators[0].token")
+            .unwrap(),
+        "sealed-alice"
+    );
+    assert_eq!(
+        resolved
+            .get_string("application.admin.operators[0].name")
+            .unwrap(),
+        "alice"
+    );
+}
+
+#[test]
+fn encrypted_file_rejects_wrong_key() {
+    let dir = TempDir::new();
+    let key_file = dir.write("secrets.key", KEY);
+    let wrong_key_file = dir.write("wrong.key", &KEY.replace('0', "f"));
+    let secrets = BTreeMap::from([("db.password".to_string(), "p".to_string())]);
+    let sealed = dir.write(
+        "secrets.enc",
+        &EncryptedFileProvider::seal(&key_file, &secrets).unwrap(),
+    );
+
+    assert!(EncryptedFileProvider::open(&sealed, &key_file).is_ok());
+    assert!(EncryptedFileProvider::open(&sealed, &wrong_key_file).is_err());
+}
@@ -0,0 +1,182 @@
+use std::{collections::HashSet, time::Duration};
+
+use kuai_saver::{api_key::API_KEY_HEADER, configuration::application::NotifierSettings};
+use reqwest::StatusCode;
+use sea_orm::{ConnectionTrait, Statement};
+use serde_json::{Value, json};
+
+use crate::{
+    helpers::{TestApp, spawn_app_with_db},
+    mock_pdd::{API_GOODS_SEARCH, goods_search_response},
+};
+
+impl TestApp {
+    async fn subscribe(&self, key: &str, body: Value) -> reqwest::Response {
+        self.client
+            .post(format!("{}/subscriptions", self.address))
+            .header(API_KEY_HEADER, key)
+            .json(&body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    async fn unsubscribe(&self, key: &str, id: &str) -> reqwest::Response {
+        self.client
+            .delete(format!("{}/subscriptions/{}", self.address, id))
+            .header(API_KEY_HEADER, key)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    async fn subscriber_key(&self) -> String {
+        self.create_key(None, &["subscribe"], 100).await
+    }
+
+    async fn alert_deliveries(&self) -> i64 {
+        self.db
+            .query_one(Statement::from_string(
+                self.db.get_database_backend(),
+                "SELECT count(*) AS count FROM alert_deliveries",
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .try_get("", "count")
+            .unwrap()
+    }
+}
+
+fn subscription(goods_id: &str, target_price: &str) -> Value {
+    json!({
+        "email": "buyer@example.com",
+        "name": "买家",
+        "platform": "pdd",
+        "goods_id": goods_id,
+        "target_price": target_price,
+    })
+}
+
+#[tokio::test]
+async fn subscriptions_require_a_key_with_the_subscribe_scope() {
+    let app = spawn_app_with_db(|_| {}).await;
+
+    let response = app
+        .client
+        .post(format!("{}/subscriptions", app.address))
+        .json(&subscription("sign-123", "5.00"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let translate_only = app.create_key(None, &["translate"], 100).await;
+    let response = app
+        .subscribe(&translate_only, subscription("sign-123", "5.00"))
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn only_the_creating_key_can_delete_a_subscription() {
+    let app = spawn_app_with_db(|_| {}).await;
+    let (owner, other) = (app.subscriber_key().await, app.subscriber_key().await);
+    let created: Value = app
+        .subscribe(&owner, subscription("sign-123", "5.00"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let id = created["id"].as_str().unwrap();
+
+    let response = app.unsubscribe(&other, id).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app.unsubscribe(&owner, id).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn emails_that_cannot_be_delivered_are_rejected() {
+    let app = spawn_app_with_db(|_| {}).await;
+    let key = app.subscriber_key().await;
+
+    for email in [
+        "buyer@",
+        "@example.com",
+        "buyer@@example.com",
+        "buyer @example.com",
+    ] {
+        let mut body = subscription("sign-123", "5.00");
+        body["email"] = json!(email);
+
+        let response = app.subscribe(&key, body).await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", email);
+    }
+}
+
+#[tokio::test]
+async fn pending_subscriptions_are_checked_in_rotation() {
+    let app = spawn_app_with_db(|settings| {
+        settings.application.price_alert.check_interval_secs = 1;
+        settings.application.price_alert.batch_size = 1;
+    })
+    .await;
+    // 券后价 10 元，两个订阅都不会触发提醒
+    app.pdd
+        .respond(API_GOODS_SEARCH, goods_search_response("sign-123", 1000, 0));
+    let key = app.subscriber_key().await;
+    for goods_id in ["goods-1", "goods-2"] {
+        let response = app.subscribe(&key, subscription(goods_id, "5.00")).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let mut checked = HashSet::new();
+    for _ in 0..50 {
+        checked = app
+            .pdd
+            .requests_for(API_GOODS_SEARCH)
+            .into_iter()
+            .map(|r| r.params["goods_sign_list"].clone())
+            .collect();
+        if checked.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    assert!(checked.contains(r#"["goods-1"]"#), "{:?}", checked);
+    assert!(checked.contains(r#"["goods-2"]"#), "{:?}", checked);
+}
+
+#[tokio::test]
+async fn failed_deliveries_stop_after_the_attempt_limit() {
+    let app = spawn_app_with_db(|settings| {
+        let alert = &mut settings.application.price_alert;
+        alert.check_interval_secs = 1;
+        alert.max_delivery_attempts = 2;
+        // 目录不存在，每次发送都会失败
+        alert.notifier = NotifierSettings::File {
+            path: "/nonexistent/kuai_saver/alerts.jsonl".to_string(),
+        };
+    })
+    .await;
+    app.pdd
+        .respond(API_GOODS_SEARCH, goods_search_response("sign-123", 1000, 0));
+    let key = app.subscriber_key().await;
+    let response = app.subscribe(&key, subscription("sign-123", "20.00")).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    for _ in 0..50 {
+        if app.alert_deliveries().await >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    // 再等两轮，不应继续重试
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+
+    assert_eq!(app.alert_deliveries().await, 2);
+}
@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::{
+    helpers::{CLIENT_ID, PID, spawn_app},
+    mock_pdd::{
+        API_GEN_SHORT_URL, API_GOODS_SEARCH, error_response, goods_search_response,
+        goods_zs_unit_generate_response,
+    },
+};
+
+const GOODS_URL: &str = "https://mobile.yangkeduo.com/goods.html?goods_id=123";
+
+#[tokio::test]
+async fn translate_link_returns_goods_info_with_short_url() {
+    let app = spawn_app().await;
+    app.pdd.respond(
+        API_GOODS_SEARCH,
+        goods_search_response("sign-123", 1000, 100),
+    );
+    app.pdd.respond(
+        API_GEN_SHORT_URL,
+        goods_zs_unit_generate_response("https://p.pinduoduo.com/abc"),
+    );
+
+    let response = app.translate_link(GOODS_URL).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["goods_id"], "sign-123");
+    assert_eq!(body["origin_price"], "10.00");
+    assert_eq!(body["coupon_discount_price"], "9.00");
+    assert_eq!(body["promotion_amount"], "0.90");
+    assert_eq!(body["short_url"], "https://p.pinduoduo.com/abc");
+
+    let searches = app.pdd.requests_for(API_GOODS_SEARCH);
+    assert_eq!(searches.len(), 1);
+    assert_eq!(searches[0].params["keyword"], GOODS_URL);
+    assert_eq!(searches[0].params["client_id"], CLIENT_ID);
+    assert_eq!(searches[0].params["pid"], PID);
+}
+
+#[tokio::test]
+async fn translate_link_serves_repeated_links_from_cache() {
+    let app = spawn_app().await;
+    app.pdd
+        .respond(API_GOODS_SEARCH, goods_search_response("sign-123", 1000, 0));
+    app.pdd.respond(
+        API_GEN_SHORT_URL,
+        goods_zs_unit_generate_response("https://p.pinduoduo.com/abc"),
+    );
+
+    for _ in 0..3 {
+        assert_eq!(app.translate_link(GOODS_URL).await.status(), StatusCode::OK);
+    }
+
+    assert_eq!(app.pdd.requests_for(API_GOODS_SEARCH).len(), 1);
+    assert_eq!(app.pdd.requests_for(API_GEN_SHORT_URL).len(), 1);
+
+    // 命中次数是累计计数器，其他测试也会增加它，只检查类型和标签
+    let metrics = app.get("/metrics").await.text().await.unwrap();
+    assert!(metrics.contains("# TYPE kuai_saver_cache_requests_total counter"));
+    assert!(metrics.contains(r#"kuai_saver_cache_requests_total{cache="goods",result="hit"}"#));
+}
+
+#[tokio::test]
+async fn translate_link_forwards_request_id_upstream() {
+    let app = spawn_app().await;
+    app.pdd
+        .respond(API_GOODS_SEARCH, goods_search_response("sign-123", 1000, 0));
+    app.pdd.respond(
+        API_GEN_SHORT_URL,
+        goods_zs_unit_generate_response("https://p.pinduoduo.com/abc"),
+    );
+
+    let response = app
+        .client
+        .get(format!("{}/translate_link", app.address))
+        .query(&[("url", GOODS_URL)])
+        .header("X-Request-Id", "test-request-id")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let requests = app.pdd.requests();
+    assert_eq!(requests.len(), 2);
+    for request in requests {
+        assert_eq!(request.request_id.as_deref(), Some("test-request-id"));
+    }
+}
+
+#[tokio::test]
+async fn translate_link_fails_when_gateway_returns_error_response() {
+    let app = spawn_app().await;
+    app.pdd
+        .respond(API_GOODS_SEARCH, error_response(50001, "业务服务错误"));
+
+    let response = app.translate_link(GOODS_URL).await;
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], 500);
+    assert!(app.pdd.requests_for(API_GEN_SHORT_URL).is_empty());
+}
+
+#[tokio::test]
+async fn translate_link_recovers_after_upstream_failure() {
+    let app = spawn_app().await;
+    app.pdd
+        .respond(API_GOODS_SEARCH, goods_search_response("sign-123", 1000, 0));
+    app.pdd.respond(
+        API_GEN_SHORT_URL,
+        goods_zs_unit_generate_response("https://p.pinduoduo.com/abc"),
+    );
+    app.pdd.fail_next(1, StatusCode::BAD_GATEWAY);
+
+    let failed = app.translate_link(GOODS_URL).await;
+    let succeeded = app.translate_link(GOODS_URL).await;
+
+    assert_eq!(failed.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(succeeded.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn business_errors_do_not_open_the_circuit_breaker() {
+    let app = spawn_app().await;
+    app.pdd
+        .respond(API_GOODS_SEARCH, error_response(50001, "业务服务错误"));
+
+    for _ in 0..6 {
+        app.translate_link(GOODS_URL).await;
+    }
+
+    assert_eq!(app.pdd.requests_for(API_GOODS_SEARCH).len(), 6);
+}
+
+#[tokio::test]
+async fn server_errors_open_the_circuit_breaker() {
+    let app = spawn_app().await;
+    app.pdd.fail_next(5, StatusCode::BAD_GATEWAY);
+
+    for _ in 0..6 {
+        let response = app.translate_link(GOODS_URL).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    // 熔断后第 6 个请求不再发往上游
+    assert_eq!(app.pdd.requests_for(API_GOODS_SEARCH).len(), 5);
+}
+
+#[tokio::test]
+async fn translate_link_rejects_unsupported_platform() {
+    let app = spawn_app().await;
+
+    let response = app.translate_link("https://item.example.com/1").await;
+
+    assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+    assert!(app.pdd.requests().is_empty());
+}
+
+#[tokio::test]
+async fn slow_upstream_does_not_block_other_requests() {
+    let app = spawn_app().await;
+    app.pdd
+        .respond(API_GOODS_SEARCH, goods_search_response("sign-123", 1000, 0));
+    app.pdd.respond(
+        API_GEN_SHORT_URL,
+        goods_zs_unit_generate_response("https://p.pinduoduo.com/abc"),
+    );
+    app.pdd.set_latency(Duration::from_millis(500));
+
+    let translate = tokio::spawn({
+        let (client, address) = (app.client.clone(), app.address.clone());
+        async move {
+            client
+                .get(format!("{}/translate_link", address))
+                .query(&[("url", GOODS_URL)])
+                .send()
+                .await
+                .unwrap()
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let ping = tokio::time::timeout(Duration::from_millis(200), app.get("/ping")).await;
+    assert_eq!(
+        ping.expect("ping blocked by slow upstream").status(),
+        StatusCode::OK
+    );
+    assert_eq!(translate.await.unwrap().status(), StatusCode::OK);
+}
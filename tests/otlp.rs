@@ -0,0 +1,114 @@
+//! OTLP 导出需要安装全局 subscriber，单独编译为一个测试程序，不影响其他测试
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{Router, body::Bytes, extract::State, routing::post};
+use kuai_saver::{
+    configuration::Settings,
+    startup::Application,
+    telemetry::{init_tracing, set_subscriber},
+};
+use opentelemetry_proto::tonic::{
+    collector::trace::v1::ExportTraceServiceRequest, common::v1::any_value::Value,
+};
+use prost::Message;
+
+const SERVICE_NAME: &str = "kuai_saver-otlp-test";
+
+/// 只接收 OTLP/HTTP protobuf 格式 span 的采集器
+#[derive(Clone, Default)]
+struct Collector {
+    requests: Arc<Mutex<Vec<ExportTraceServiceRequest>>>,
+}
+
+impl Collector {
+    async fn start(&self) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let router = Router::new()
+            .route("/v1/traces", post(receive))
+            .with_state(self.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        address
+    }
+
+    /// 收到的 span 名称，只包含资源属性 `service.name` 为 [`SERVICE_NAME`] 的
+    fn span_names(&self) -> Vec<String> {
+        let requests = self.requests.lock().unwrap();
+        requests
+            .iter()
+            .flat_map(|request| &request.resource_spans)
+            .filter(|resource_spans| {
+                resource_spans
+                    .resource
+                    .iter()
+                    .flat_map(|resource| &resource.attributes)
+                    .any(|attribute| {
+                        attribute.key == "service.name"
+                            && attribute.value.as_ref().and_then(|v| v.value.as_ref())
+                                == Some(&Value::StringValue(SERVICE_NAME.to_string()))
+                    })
+            })
+            .flat_map(|resource_spans| &resource_spans.scope_spans)
+            .flat_map(|scope_spans| &scope_spans.spans)
+            .map(|span| span.name.clone())
+            .collect()
+    }
+}
+
+async fn receive(State(collector): State<Collector>, body: Bytes) {
+    let request = ExportTraceServiceRequest::decode(body).expect("Invalid OTLP request");
+    collector.requests.lock().unwrap().push(request);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn request_spans_are_exported_to_the_collector() {
+    let collector = Collector::default();
+    let endpoint = format!("{}/v1/traces", collector.start().await);
+    let settings: Settings = config::Config::builder()
+        .add_source(config::File::with_name("configuration/base.yaml"))
+        .set_override("application.host", "127.0.0.1")
+        .and_then(|b| b.set_override("application.port", 0))
+        .and_then(|b| b.set_override("application.pdd.client_id", "test-client-id"))
+        .and_then(|b| b.set_override("application.pdd.client_secret", "test-client-secret"))
+        .and_then(|b| b.set_override("application.pdd.pid", "test-pid"))
+        .and_then(|b| b.set_override("db.port", 1))
+        .and_then(|b| b.set_override("db.password", "password"))
+        .and_then(|b| b.set_override("log.targets", Vec::<String>::new()))
+        .and_then(|b| b.set_override("log.otlp.endpoint", endpoint))
+        .and_then(|b| b.set_override("log.otlp.protocol", "http"))
+        .and_then(|b| b.set_override("log.otlp.service_name", SERVICE_NAME))
+        .and_then(|b| b.build())
+        .and_then(|c| c.try_deserialize())
+        .expect("Failed to load test configuration");
+
+    let (subscriber, guard) = init_tracing(settings.log.clone()).unwrap();
+    set_subscriber(subscriber);
+    let app = Application::build(settings, guard.log_filters())
+        .await
+        .unwrap();
+    let address = format!("http://127.0.0.1:{}/ping", app.port());
+    let shutdown = app.shutdown_token();
+    tokio::spawn(app.run_until_stopped());
+
+    let response = reqwest::get(address).await.unwrap();
+    assert!(response.status().is_success());
+    shutdown.cancel();
+    // 关闭时导出尚未发送的 span，关闭过程会阻塞线程
+    tokio::task::spawn_blocking(move || drop(guard))
+        .await
+        .unwrap();
+
+    let mut names = Vec::new();
+    for _ in 0..50 {
+        names = collector.span_names();
+        if !names.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(names.iter().any(|name| name == "request"), "{:?}", names);
+}